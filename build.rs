@@ -29,6 +29,11 @@ use std::{
 
 #[allow(clippy::too_many_lines)]
 fn main() {
+    // Without the `ffi` feature nothing links to RandomX, so it is not built
+    if env::var_os("CARGO_FEATURE_FFI").is_none() {
+        return;
+    }
+
     let out_dir = env::var("OUT_DIR").unwrap();
     let project_dir = Path::new(&out_dir);
     let cargo_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
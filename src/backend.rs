@@ -0,0 +1,114 @@
+// Copyright 2026. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+#[cfg(feature = "ffi")]
+use crate::{RandomXCache, RandomXDataset, RandomXVM};
+use crate::{RandomXError, RandomXFlag, RandomXHash};
+
+/// An implementation of RandomX: how caches, datasets and VMs are created, and how VMs hash.
+///
+/// Code that is generic over a backend can run on the C library through [`FfiBackend`], or on any other
+/// implementation, without changes.
+pub trait RandomXBackend {
+    /// The cache used for light verification and dataset construction.
+    type Cache: Clone;
+    /// The dataset used by fast-mode VMs.
+    type Dataset: Clone;
+    /// A VM that calculates hashes.
+    type VM;
+
+    /// Returns true if the backend can create datasets and fast-mode VMs, i.e. supports FLAG_FULL_MEM.
+    fn supports_full_mem(&self) -> bool;
+
+    /// Creates a cache initialized with `key`, see [`RandomXCache::new`].
+    fn create_cache(&self, flags: RandomXFlag, key: &[u8]) -> Result<Self::Cache, RandomXError>;
+
+    /// Creates a dataset from `cache`, initializing the items from `start` onwards, see [`RandomXDataset::new`].
+    fn create_dataset(&self, flags: RandomXFlag, cache: Self::Cache, start: u32)
+        -> Result<Self::Dataset, RandomXError>;
+
+    /// Creates a VM that hashes with `cache` in light mode, or with `dataset` if `flags` contains FLAG_FULL_MEM, see
+    /// [`RandomXVM::new`].
+    fn create_vm(
+        &self,
+        flags: RandomXFlag,
+        cache: Option<Self::Cache>,
+        dataset: Option<Self::Dataset>,
+    ) -> Result<Self::VM, RandomXError>;
+
+    /// Calculates the hash of `input` with `vm`.
+    fn calculate_hash(&self, vm: &Self::VM, input: &[u8]) -> Result<RandomXHash, RandomXError>;
+
+    /// Calculates the hashes of `inputs` with `vm`, in order.
+    fn calculate_hash_set(&self, vm: &Self::VM, inputs: &[&[u8]]) -> Result<Vec<RandomXHash>, RandomXError> {
+        if inputs.is_empty() {
+            return Err(RandomXError::ParameterError("input was empty".to_string()));
+        }
+        inputs.iter().map(|input| self.calculate_hash(vm, input)).collect()
+    }
+}
+
+#[cfg(feature = "ffi")]
+/// The RandomX C++ library, through [`RandomXCache`], [`RandomXDataset`] and [`RandomXVM`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FfiBackend;
+
+#[cfg(feature = "ffi")]
+impl RandomXBackend for FfiBackend {
+    type Cache = RandomXCache;
+    type Dataset = RandomXDataset;
+    type VM = RandomXVM;
+
+    fn supports_full_mem(&self) -> bool {
+        true
+    }
+
+    fn create_cache(&self, flags: RandomXFlag, key: &[u8]) -> Result<RandomXCache, RandomXError> {
+        RandomXCache::new(flags, key)
+    }
+
+    fn create_dataset(
+        &self,
+        flags: RandomXFlag,
+        cache: RandomXCache,
+        start: u32,
+    ) -> Result<RandomXDataset, RandomXError> {
+        RandomXDataset::new(flags, cache, start)
+    }
+
+    fn create_vm(
+        &self,
+        flags: RandomXFlag,
+        cache: Option<RandomXCache>,
+        dataset: Option<RandomXDataset>,
+    ) -> Result<RandomXVM, RandomXError> {
+        RandomXVM::new(flags, cache, dataset)
+    }
+
+    fn calculate_hash(&self, vm: &RandomXVM, input: &[u8]) -> Result<RandomXHash, RandomXError> {
+        vm.calculate_hash(input)
+    }
+
+    fn calculate_hash_set(&self, vm: &RandomXVM, inputs: &[&[u8]]) -> Result<Vec<RandomXHash>, RandomXError> {
+        vm.calculate_hash_set(inputs)
+    }
+}
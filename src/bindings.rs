@@ -20,24 +20,32 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
+#[cfg(feature = "ffi")]
 use libc::{c_uint, c_ulong, c_void};
 pub const RANDOMX_HASH_SIZE: u32 = 32;
+/// The cache size in KiB, `RANDOMX_ARGON_MEMORY` in RandomX's `configuration.h`.
+#[cfg(any(feature = "ffi", feature = "pure-rust"))]
+pub const RANDOMX_ARGON_MEMORY: u32 = 262_144;
 
+#[cfg(feature = "ffi")]
 #[repr(C)]
 pub struct randomx_dataset {
     _unused: [u8; 0],
 }
 
+#[cfg(feature = "ffi")]
 #[repr(C)]
 pub struct randomx_cache {
     _unused: [u8; 0],
 }
 
+#[cfg(feature = "ffi")]
 #[repr(C)]
 pub struct randomx_vm {
     _unused: [u8; 0],
 }
 
+#[cfg(feature = "ffi")]
 extern "C" {
     pub fn randomx_alloc_cache(flags: c_uint) -> *mut randomx_cache;
     pub fn randomx_init_cache(cache: *mut randomx_cache, key: *const c_void, keySize: usize);
@@ -77,7 +85,7 @@ extern "C" {
     pub fn randomx_get_flags() -> c_uint;
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "ffi"))]
 mod tests {
     use std::ptr;
 
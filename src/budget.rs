@@ -0,0 +1,159 @@
+// Copyright 2026. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::convert::TryFrom;
+
+use crate::{RandomXCache, RandomXDataset, RandomXError, RandomXFlag};
+
+/// The size of a VM scratchpad in bytes, which dominates the memory used by each VM.
+const VM_MEMORY_SIZE: u64 = 2 * 1024 * 1024;
+
+/// A RandomX configuration that fits into a memory budget, see [`ModeConfig::for_budget`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ModeConfig {
+    /// The flags to create the cache, dataset and VMs with. Includes FLAG_FULL_MEM in fast mode.
+    pub flags: RandomXFlag,
+    /// True if a dataset should be allocated, i.e. fast mode. Otherwise VMs hash in light mode with the cache only.
+    pub full_mem: bool,
+    /// The number of VMs that fit into the budget, at most the requested maximum.
+    pub vm_count: usize,
+}
+
+impl ModeConfig {
+    /// Selects fast mode if the cache, the dataset and at least one VM fit into `budget` bytes, and light mode if only
+    /// the cache and at least one VM fit. Returns `RandomXError::InsufficientMemory` if neither fits.
+    ///
+    /// `max_vms` is the maximum number of VMs to fit into the remaining budget.
+    pub fn for_budget(budget: u64, max_vms: usize) -> Result<ModeConfig, RandomXError> {
+        let dataset_size = u64::try_from(RandomXDataset::memory_size()?)?;
+        let (full_mem, vm_count) = select(budget, max_vms, dataset_size)?;
+        let mut flags = RandomXFlag::get_recommended_flags();
+        flags.set(RandomXFlag::FLAG_FULL_MEM, full_mem);
+        Ok(ModeConfig {
+            flags,
+            full_mem,
+            vm_count,
+        })
+    }
+
+    /// Selects a mode like [`ModeConfig::for_budget`], using the memory the OS reports as available as the budget.
+    ///
+    /// Available memory is only read on Linux, from `/proc/meminfo`.
+    pub fn for_available_memory(max_vms: usize) -> Result<ModeConfig, RandomXError> {
+        ModeConfig::for_budget(available_memory()?, max_vms)
+    }
+}
+
+/// Returns whether fast mode fits into `budget` and how many VMs fit next to it.
+fn select(budget: u64, max_vms: usize, dataset_size: u64) -> Result<(bool, usize), RandomXError> {
+    if max_vms == 0 {
+        return Err(RandomXError::ParameterError(
+            "max_vms must be greater than 0".to_string(),
+        ));
+    }
+    let cache_size = u64::try_from(RandomXCache::MEMORY_SIZE)?;
+    let fast_size = cache_size.saturating_add(dataset_size);
+    for (full_mem, fixed) in [(true, fast_size), (false, cache_size)] {
+        if let Some(remaining) = budget.checked_sub(fixed) {
+            let vm_count = usize::try_from(remaining / VM_MEMORY_SIZE).unwrap_or(usize::MAX);
+            if vm_count > 0 {
+                return Ok((full_mem, vm_count.min(max_vms)));
+            }
+        }
+    }
+    Err(RandomXError::InsufficientMemory {
+        required: cache_size + VM_MEMORY_SIZE,
+        available: budget,
+    })
+}
+
+#[cfg(target_os = "linux")]
+fn available_memory() -> Result<u64, RandomXError> {
+    let meminfo = std::fs::read_to_string("/proc/meminfo")
+        .map_err(|e| RandomXError::Other(format!("Could not read /proc/meminfo: {e}")))?;
+    crate::hugepages::meminfo_value(&meminfo, "MemAvailable:")
+        .and_then(|kib| kib.checked_mul(1024))
+        .ok_or_else(|| RandomXError::Other("MemAvailable not found in /proc/meminfo".to_string()))
+}
+
+#[cfg(not(target_os = "linux"))]
+fn available_memory() -> Result<u64, RandomXError> {
+    Err(RandomXError::Other(
+        "Available memory can only be read on Linux, use ModeConfig::for_budget instead".to_string(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{select, VM_MEMORY_SIZE};
+    use crate::{ModeConfig, RandomXError, RandomXFlag};
+
+    const MIB: u64 = 1024 * 1024;
+    const CACHE: u64 = 256 * MIB;
+    const DATASET: u64 = 2080 * MIB - 64;
+
+    #[test]
+    fn select_mode() {
+        #[rustfmt::skip]
+        let cases = [
+            // (budget, max_vms, expected)
+            (CACHE + DATASET + VM_MEMORY_SIZE, 8, Some((true, 1))),
+            (CACHE + DATASET + 4 * VM_MEMORY_SIZE, 8, Some((true, 4))),
+            (128 * 1024 * MIB, 8, Some((true, 8))),
+            (u64::MAX, usize::MAX, Some((true, usize::MAX))),
+            (CACHE + DATASET + VM_MEMORY_SIZE - 1, 8, Some((false, 8))),
+            (CACHE + DATASET, 2, Some((false, 2))),
+            (CACHE + VM_MEMORY_SIZE, 8, Some((false, 1))),
+            (2 * 1024 * MIB, 4, Some((false, 4))),
+            (CACHE + VM_MEMORY_SIZE - 1, 8, None),
+            (CACHE, 8, None),
+            (0, 8, None),
+        ];
+        for (budget, max_vms, expected) in cases {
+            match (select(budget, max_vms, DATASET), expected) {
+                (Ok(selected), Some(expected)) => assert_eq!(selected, expected, "budget: {}", budget),
+                (Err(RandomXError::InsufficientMemory { required, available }), None) => {
+                    assert_eq!(required, CACHE + VM_MEMORY_SIZE);
+                    assert_eq!(available, budget);
+                },
+                (result, _) => panic!("budget: {}, unexpected result: {:?}", budget, result),
+            }
+        }
+        assert!(matches!(
+            select(u64::MAX, 0, DATASET),
+            Err(RandomXError::ParameterError(_))
+        ));
+    }
+
+    #[test]
+    fn mode_config_flags() {
+        let fast = ModeConfig::for_budget(4 * 1024 * MIB, 2).unwrap();
+        assert!(fast.full_mem);
+        assert!(fast.flags.contains(RandomXFlag::FLAG_FULL_MEM));
+        assert_eq!(fast.vm_count, 2);
+
+        let light = ModeConfig::for_budget(512 * MIB, 2).unwrap();
+        assert!(!light.full_mem);
+        assert!(!light.flags.contains(RandomXFlag::FLAG_FULL_MEM));
+        assert_eq!(light.flags, RandomXFlag::get_recommended_flags());
+    }
+}
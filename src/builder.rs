@@ -0,0 +1,277 @@
+// Copyright 2026. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use thiserror::Error;
+
+#[cfg(feature = "ffi")]
+use crate::{RandomXCache, RandomXDataset, RandomXError, RandomXFlag, RandomXVM};
+
+/// An invalid combination of flags for a [`RandomXVM`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum FlagConflict {
+    #[error("FLAG_SECURE requires FLAG_JIT")]
+    SecureWithoutJit,
+    #[error("Argon2 flags only apply to caches, not to VMs")]
+    Argon2OnVm,
+    #[error("FLAG_FULL_MEM requires a dataset, use fast mode instead")]
+    FullMemWithoutDataset,
+    #[error("FLAG_JIT is not supported on this platform")]
+    JitNotSupported,
+    #[error("FLAG_HARD_AES is not supported by this CPU")]
+    HardAesNotSupported,
+}
+
+#[cfg(feature = "ffi")]
+#[derive(Debug, Clone)]
+enum VmMode {
+    Light(RandomXCache),
+    Fast(RandomXDataset),
+}
+
+#[cfg(feature = "ffi")]
+/// Builds a [`RandomXVM`] in light or fast mode, validating the flag combination before it is passed to RandomX.
+/// Options and raw flags are applied in call order, so a later call overrides an earlier one.
+#[derive(Debug, Clone)]
+pub struct RandomXVMBuilder {
+    mode: VmMode,
+    flags: RandomXFlag,
+    fallback: bool,
+}
+
+#[cfg(feature = "ffi")]
+impl RandomXVMBuilder {
+    /// Creates a builder for a light-mode VM, which hashes using only the `cache`.
+    pub fn light(cache: RandomXCache) -> Self {
+        Self::with_mode(VmMode::Light(cache))
+    }
+
+    /// Creates a builder for a fast-mode VM, which hashes using the `dataset`. FLAG_FULL_MEM is set automatically.
+    pub fn fast(dataset: RandomXDataset) -> Self {
+        Self::with_mode(VmMode::Fast(dataset))
+    }
+
+    fn with_mode(mode: VmMode) -> Self {
+        RandomXVMBuilder {
+            mode,
+            flags: RandomXFlag::FLAG_DEFAULT,
+            fallback: false,
+        }
+    }
+
+    /// Enables the options recommended for this platform, see [`RandomXFlag::get_recommended_flags`].
+    pub fn recommended(self) -> Self {
+        let recommended = RandomXFlag::get_recommended_flags();
+        self.jit(recommended.contains(RandomXFlag::FLAG_JIT))
+            .hard_aes(recommended.contains(RandomXFlag::FLAG_HARD_AES))
+            .secure(recommended.contains(RandomXFlag::FLAG_SECURE))
+    }
+
+    /// Use JIT compilation support (FLAG_JIT).
+    pub fn jit(mut self, enabled: bool) -> Self {
+        self.flags.set(RandomXFlag::FLAG_JIT, enabled);
+        self
+    }
+
+    /// Use hardware accelerated AES (FLAG_HARD_AES).
+    pub fn hard_aes(mut self, enabled: bool) -> Self {
+        self.flags.set(RandomXFlag::FLAG_HARD_AES, enabled);
+        self
+    }
+
+    /// Never map JIT pages as writable and executable at the same time (FLAG_SECURE). Requires JIT.
+    pub fn secure(mut self, enabled: bool) -> Self {
+        self.flags.set(RandomXFlag::FLAG_SECURE, enabled);
+        self
+    }
+
+    /// Allocate the VM scratchpad in large pages (FLAG_LARGE_PAGES).
+    pub fn large_pages(mut self, enabled: bool) -> Self {
+        self.flags.set(RandomXFlag::FLAG_LARGE_PAGES, enabled);
+        self
+    }
+
+    /// If RandomX cannot create the VM with the requested options, retry with fewer of them, see
+    /// [`RandomXVM::new_with_fallback`]. The options that were used are reported by [`RandomXVM::flags`].
+    pub fn fallback(mut self, enabled: bool) -> Self {
+        self.fallback = enabled;
+        self
+    }
+
+    /// Adds raw `flags`, e.g. when migrating from [`RandomXVM::new`]. They are validated together with the typed
+    /// options when the VM is built, and a later typed option can still clear them.
+    pub fn with_flags(mut self, flags: RandomXFlag) -> Self {
+        self.flags |= flags;
+        self
+    }
+
+    /// Returns the flags the VM will be created with, or the reason the combination is invalid.
+    pub fn flags(&self) -> Result<RandomXFlag, FlagConflict> {
+        let mut flags = self.flags;
+        let fast = matches!(self.mode, VmMode::Fast(_));
+        validate(flags, fast, RandomXFlag::get_recommended_flags())?;
+        if fast {
+            flags |= RandomXFlag::FLAG_FULL_MEM;
+        }
+        Ok(flags)
+    }
+
+    /// Validates the flags and creates the VM, error on failure.
+    pub fn build(self) -> Result<RandomXVM, RandomXError> {
+        let flags = self.flags()?;
+        let (cache, dataset) = match self.mode {
+            VmMode::Light(cache) => (Some(cache), None),
+            VmMode::Fast(dataset) => (None, Some(dataset)),
+        };
+        if self.fallback {
+            RandomXVM::new_with_fallback(flags, cache, dataset)
+        } else {
+            RandomXVM::new(flags, cache, dataset)
+        }
+    }
+}
+
+#[cfg(feature = "ffi")]
+/// Checks `flags` for a VM in fast or light mode, on a platform that supports the `supported` flags.
+fn validate(flags: RandomXFlag, fast: bool, supported: RandomXFlag) -> Result<(), FlagConflict> {
+    if flags.intersects(RandomXFlag::FLAG_ARGON2) {
+        return Err(FlagConflict::Argon2OnVm);
+    }
+    if !fast && flags.contains(RandomXFlag::FLAG_FULL_MEM) {
+        return Err(FlagConflict::FullMemWithoutDataset);
+    }
+    if flags.contains(RandomXFlag::FLAG_SECURE) && !flags.contains(RandomXFlag::FLAG_JIT) {
+        return Err(FlagConflict::SecureWithoutJit);
+    }
+    if flags.contains(RandomXFlag::FLAG_JIT) && !supported.contains(RandomXFlag::FLAG_JIT) {
+        return Err(FlagConflict::JitNotSupported);
+    }
+    if flags.contains(RandomXFlag::FLAG_HARD_AES) && !supported.contains(RandomXFlag::FLAG_HARD_AES) {
+        return Err(FlagConflict::HardAesNotSupported);
+    }
+    Ok(())
+}
+
+#[cfg(all(test, feature = "ffi"))]
+mod tests {
+    use super::validate;
+    use crate::{FlagConflict, RandomXCache, RandomXError, RandomXFlag, RandomXVM, RandomXVMBuilder};
+
+    #[test]
+    fn flag_combinations() {
+        let all = RandomXFlag::FLAG_JIT | RandomXFlag::FLAG_HARD_AES;
+        let none = RandomXFlag::FLAG_DEFAULT;
+        let jit = RandomXFlag::FLAG_JIT;
+        let aes = RandomXFlag::FLAG_HARD_AES;
+        let secure = RandomXFlag::FLAG_SECURE;
+        let pages = RandomXFlag::FLAG_LARGE_PAGES;
+        let full = RandomXFlag::FLAG_FULL_MEM;
+        #[rustfmt::skip]
+        let cases = [
+            // (flags, fast, supported, expected)
+            (none, false, all, Ok(())),
+            (none, true, none, Ok(())),
+            (jit | aes | pages, false, all, Ok(())),
+            (jit | aes | secure | pages, true, all, Ok(())),
+            (jit | secure, false, jit, Ok(())),
+            (full, true, none, Ok(())),
+            (secure, false, all, Err(FlagConflict::SecureWithoutJit)),
+            (secure | aes, true, all, Err(FlagConflict::SecureWithoutJit)),
+            (RandomXFlag::FLAG_ARGON2, false, all, Err(FlagConflict::Argon2OnVm)),
+            (RandomXFlag::FLAG_ARGON2_SSSE3, true, all, Err(FlagConflict::Argon2OnVm)),
+            (RandomXFlag::FLAG_ARGON2_AVX2 | jit, false, all, Err(FlagConflict::Argon2OnVm)),
+            (full, false, all, Err(FlagConflict::FullMemWithoutDataset)),
+            (full | jit, false, all, Err(FlagConflict::FullMemWithoutDataset)),
+            (jit, false, aes, Err(FlagConflict::JitNotSupported)),
+            (jit | secure, true, none, Err(FlagConflict::JitNotSupported)),
+            (aes, false, jit, Err(FlagConflict::HardAesNotSupported)),
+            (aes | jit, true, jit, Err(FlagConflict::HardAesNotSupported)),
+        ];
+        for (flags, fast, supported, expected) in cases {
+            assert_eq!(
+                validate(flags, fast, supported),
+                expected,
+                "flags: {:?}, fast: {}, supported: {:?}",
+                flags,
+                fast,
+                supported
+            );
+        }
+    }
+
+    #[test]
+    fn options_last_call_wins() {
+        let cache = RandomXCache::new(RandomXFlag::FLAG_DEFAULT, b"Key").unwrap();
+        let builder = || RandomXVMBuilder::light(cache.clone());
+        let jit = RandomXFlag::FLAG_JIT;
+        let pages = RandomXFlag::FLAG_LARGE_PAGES;
+        let none = RandomXFlag::FLAG_DEFAULT;
+        let cases = [
+            // (builder, expected)
+            (builder().large_pages(true), pages),
+            (builder().large_pages(true).large_pages(false), none),
+            (builder().with_flags(pages).large_pages(false), none),
+            (builder().large_pages(false).with_flags(pages), pages),
+            (builder().with_flags(jit | pages).jit(false), pages),
+            (builder().jit(true).with_flags(pages).jit(false), pages),
+            (builder().with_flags(pages).with_flags(none), pages),
+        ];
+        for (i, (builder, expected)) in cases.iter().enumerate() {
+            assert_eq!(builder.flags(), Ok(*expected), "case {}", i);
+        }
+    }
+
+    #[test]
+    fn build_light_vm() {
+        let flags = RandomXFlag::get_recommended_flags();
+        let cache = RandomXCache::new(flags, b"Key").unwrap();
+
+        let builder = RandomXVMBuilder::light(cache.clone()).recommended().large_pages(false);
+        assert!(!builder.flags().unwrap().contains(RandomXFlag::FLAG_FULL_MEM));
+        let vm = builder.build().unwrap();
+        let expected = RandomXVM::new(flags, Some(cache.clone()), None).unwrap();
+        assert_eq!(
+            vm.calculate_hash(b"Input").unwrap(),
+            expected.calculate_hash(b"Input").unwrap()
+        );
+
+        let vm = RandomXVMBuilder::light(cache.clone())
+            .recommended()
+            .large_pages(true)
+            .fallback(true)
+            .build()
+            .unwrap();
+        assert!(vm.flags().contains(RandomXFlag::FLAG_HARD_AES & flags));
+
+        let result = RandomXVMBuilder::light(cache.clone()).jit(false).secure(true).build();
+        assert!(matches!(
+            result,
+            Err(RandomXError::InvalidFlags(FlagConflict::SecureWithoutJit))
+        ));
+        let result = RandomXVMBuilder::light(cache)
+            .with_flags(RandomXFlag::FLAG_FULL_MEM)
+            .build();
+        assert!(matches!(
+            result,
+            Err(RandomXError::InvalidFlags(FlagConflict::FullMemWithoutDataset))
+        ));
+    }
+}
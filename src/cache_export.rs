@@ -0,0 +1,120 @@
+// Copyright 2026. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::convert::TryFrom;
+#[cfg(any(test, feature = "pure-rust"))]
+use std::convert::TryInto;
+
+#[cfg(any(test, feature = "pure-rust"))]
+use crate::bindings::RANDOMX_ARGON_MEMORY;
+use crate::{
+    checksum::{checksum, key_fingerprint, CHECKSUM_SEED},
+    RandomXError,
+};
+
+const MAGIC: [u8; 8] = *b"RXCACHE\0";
+const VERSION: u32 = 1;
+const KEY_OFFSET: usize = 8 + 4 + 4 + 8 + 8;
+
+/// Encodes exported cache memory: a header with the key, its fingerprint and a checksum of the memory, followed by
+/// the memory.
+pub(crate) fn encode(key: &[u8], memory: &[u8]) -> Result<Vec<u8>, RandomXError> {
+    let mut bytes = Vec::with_capacity(KEY_OFFSET + key.len() + memory.len());
+    bytes.extend_from_slice(&MAGIC);
+    bytes.extend_from_slice(&VERSION.to_le_bytes());
+    bytes.extend_from_slice(&u32::try_from(key.len())?.to_le_bytes());
+    bytes.extend_from_slice(&key_fingerprint(key).to_le_bytes());
+    bytes.extend_from_slice(&checksum(CHECKSUM_SEED, memory).to_le_bytes());
+    bytes.extend_from_slice(key);
+    bytes.extend_from_slice(memory);
+    Ok(bytes)
+}
+
+/// Decodes cache memory exported for `key`, returning `RandomXError::CacheExportMismatch` if it was exported for
+/// another key or by an unsupported version, and `RandomXError::CacheExportCorrupt` if it is damaged.
+#[cfg(any(test, feature = "pure-rust"))]
+pub(crate) fn decode<'a>(key: &[u8], bytes: &'a [u8]) -> Result<&'a [u8], RandomXError> {
+    let corrupt = |reason: &str| RandomXError::CacheExportCorrupt(reason.to_string());
+    if bytes.len() < KEY_OFFSET || bytes[..8] != MAGIC {
+        return Err(corrupt("not an exported cache"));
+    }
+    let u32_at = |offset: usize| u32::from_le_bytes(bytes[offset..offset + 4].try_into().expect("4 bytes"));
+    let u64_at = |offset: usize| u64::from_le_bytes(bytes[offset..offset + 8].try_into().expect("8 bytes"));
+    if u32_at(8) != VERSION {
+        return Err(RandomXError::CacheExportMismatch(format!(
+            "unsupported version {}, expected {VERSION}",
+            u32_at(8)
+        )));
+    }
+    let key_len = usize::try_from(u32_at(12))?;
+    let recorded_key = bytes
+        .get(KEY_OFFSET..KEY_OFFSET + key_len)
+        .ok_or_else(|| corrupt("truncated key"))?;
+    if u64_at(16) != key_fingerprint(key) || recorded_key != key {
+        return Err(RandomXError::CacheExportMismatch(
+            "cache was exported for another key".to_string(),
+        ));
+    }
+    let memory = &bytes[KEY_OFFSET + key_len..];
+    if memory.len() != RANDOMX_ARGON_MEMORY as usize * 1024 {
+        return Err(corrupt("cache memory has the wrong size"));
+    }
+    if checksum(CHECKSUM_SEED, memory) != u64_at(24) {
+        return Err(corrupt("checksum does not match"));
+    }
+    Ok(memory)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode, encode};
+    use crate::{bindings::RANDOMX_ARGON_MEMORY, RandomXError};
+
+    #[test]
+    fn cache_export_round_trip() {
+        let memory = (0..=u8::MAX)
+            .cycle()
+            .take(RANDOMX_ARGON_MEMORY as usize * 1024)
+            .collect::<Vec<_>>();
+        let mut bytes = encode(b"Key", &memory).unwrap();
+        assert!(decode(b"Key", &bytes).unwrap() == memory.as_slice());
+        assert!(matches!(
+            decode(b"Kez", &bytes),
+            Err(RandomXError::CacheExportMismatch(_))
+        ));
+
+        let last = bytes.len() - 1;
+        bytes[last] ^= 1;
+        assert!(matches!(
+            decode(b"Key", &bytes),
+            Err(RandomXError::CacheExportCorrupt(_))
+        ));
+        assert!(matches!(
+            decode(b"Key", &bytes[..last]),
+            Err(RandomXError::CacheExportCorrupt(_))
+        ));
+        assert!(matches!(
+            decode(b"Key", &bytes[..10]),
+            Err(RandomXError::CacheExportCorrupt(_))
+        ));
+    }
+}
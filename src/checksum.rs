@@ -0,0 +1,56 @@
+// Copyright 2026. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+pub(crate) const CHECKSUM_SEED: u64 = 0xcbf2_9ce4_8422_2325;
+
+/// Folds `data` into `sum`, 8 bytes at a time, with the FNV-1a step. This detects corruption, it is not a
+/// cryptographic hash. Feeding data in chunks gives the same result as feeding it at once if every chunk but the last
+/// is a multiple of 8 bytes long.
+pub(crate) fn checksum(mut sum: u64, data: &[u8]) -> u64 {
+    for word in data.chunks(8) {
+        let mut bytes = [0u8; 8];
+        bytes[..word.len()].copy_from_slice(word);
+        sum = (sum ^ u64::from_le_bytes(bytes)).wrapping_mul(0x0100_0000_01b3);
+    }
+    sum
+}
+
+/// Identifies `key` in file headers and shared memory segment names.
+pub(crate) fn key_fingerprint(key: &[u8]) -> u64 {
+    checksum(checksum(CHECKSUM_SEED, &(key.len() as u64).to_le_bytes()), key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{checksum, key_fingerprint, CHECKSUM_SEED};
+
+    #[test]
+    fn checksum_in_chunks() {
+        let data = (0..100u8).collect::<Vec<_>>();
+        assert_eq!(
+            checksum(checksum(CHECKSUM_SEED, &data[..64]), &data[64..]),
+            checksum(CHECKSUM_SEED, &data)
+        );
+        assert_ne!(checksum(CHECKSUM_SEED, &data[..99]), checksum(CHECKSUM_SEED, &data));
+        assert_ne!(key_fingerprint(b"Key"), key_fingerprint(b"Key\0"));
+    }
+}
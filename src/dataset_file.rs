@@ -0,0 +1,323 @@
+// Copyright 2026. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::{
+    convert::{TryFrom, TryInto},
+    fs::{self, File},
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::{Path, PathBuf},
+    ptr,
+};
+
+use crate::{
+    bindings::randomx_get_dataset_memory,
+    checksum::{checksum, key_fingerprint, CHECKSUM_SEED},
+    RandomXCache,
+    RandomXDataset,
+    RandomXError,
+    RandomXFlag,
+};
+
+const MAGIC: [u8; 8] = *b"RXDATASE";
+const VERSION: u32 = 1;
+/// The size of the header without the key that follows it.
+const HEADER_SIZE: usize = 8 + 4 + 4 + 4 + 8 + RandomXDataset::ITEM_SIZE + 8 + 4;
+const CHUNK_SIZE: usize = 1024 * 1024;
+
+/// The header of a dataset file, followed by the key the dataset was built for and the items of the dataset.
+///
+/// The first item doubles as the fingerprint of the RandomX parameters: it depends on every parameter used to build
+/// the cache and the dataset, so a file written with other parameters is detected before its items are read.
+#[derive(Debug, PartialEq, Eq)]
+struct Header {
+    version: u32,
+    item_size: u32,
+    item_count: u32,
+    key_fingerprint: u64,
+    first_item: [u8; RandomXDataset::ITEM_SIZE],
+    checksum: u64,
+    key: Vec<u8>,
+}
+
+impl Header {
+    fn to_bytes(&self) -> Result<Vec<u8>, RandomXError> {
+        let mut bytes = Vec::with_capacity(HEADER_SIZE + self.key.len());
+        let fields: [&[u8]; 9] = [
+            &MAGIC,
+            &self.version.to_le_bytes(),
+            &self.item_size.to_le_bytes(),
+            &self.item_count.to_le_bytes(),
+            &self.key_fingerprint.to_le_bytes(),
+            &self.first_item,
+            &self.checksum.to_le_bytes(),
+            &u32::try_from(self.key.len())?.to_le_bytes(),
+            &self.key,
+        ];
+        for field in fields {
+            bytes.extend_from_slice(field);
+        }
+        Ok(bytes)
+    }
+
+    /// Reads the header and the key that follows it from `reader`.
+    fn read<R: Read>(reader: &mut R) -> Result<Self, RandomXError> {
+        let mut bytes = [0u8; HEADER_SIZE];
+        reader.read_exact(&mut bytes).map_err(read_error)?;
+        if bytes[..8] != MAGIC {
+            return Err(RandomXError::DatasetFileCorrupt("not a dataset file".to_string()));
+        }
+        let u32_at = |offset: usize| u32::from_le_bytes(bytes[offset..offset + 4].try_into().expect("4 bytes"));
+        let u64_at = |offset: usize| u64::from_le_bytes(bytes[offset..offset + 8].try_into().expect("8 bytes"));
+        let mut first_item = [0u8; RandomXDataset::ITEM_SIZE];
+        first_item.copy_from_slice(&bytes[28..28 + RandomXDataset::ITEM_SIZE]);
+        // The key is read through `take`, so a corrupt length cannot make this allocate more than the file holds
+        let key_len = u32_at(HEADER_SIZE - 4);
+        let mut key = Vec::new();
+        reader
+            .take(u64::from(key_len))
+            .read_to_end(&mut key)
+            .map_err(io_error)?;
+        if key.len() != usize::try_from(key_len)? {
+            return Err(RandomXError::DatasetFileCorrupt("file is truncated".to_string()));
+        }
+        Ok(Self {
+            version: u32_at(8),
+            item_size: u32_at(12),
+            item_count: u32_at(16),
+            key_fingerprint: u64_at(20),
+            first_item,
+            checksum: u64_at(HEADER_SIZE - 12),
+            key,
+        })
+    }
+}
+
+impl RandomXDataset {
+    /// Writes the dataset to a file at `path`, so it can be loaded with [`RandomXDataset::load_from`] instead of being
+    /// initialized again.
+    ///
+    /// The file is written next to `path` under a temporary name and then renamed to `path`, so an existing file at
+    /// `path` is only replaced once the new one is complete.
+    pub fn save_to<P: AsRef<Path>>(&self, path: P) -> Result<(), RandomXError> {
+        let memory = self.memory()?;
+        let header = Header {
+            version: VERSION,
+            item_size: u32::try_from(RandomXDataset::ITEM_SIZE)?,
+            item_count: self.inner.dataset_count,
+            key_fingerprint: key_fingerprint(self.key()),
+            first_item: *self.item(0)?,
+            checksum: checksum(CHECKSUM_SEED, memory),
+            key: self.key().to_vec(),
+        }
+        .to_bytes()?;
+        let path = path.as_ref();
+        let mut temp_path = path.as_os_str().to_owned();
+        temp_path.push(".tmp");
+        let temp_path = PathBuf::from(temp_path);
+        let result = write_file(&temp_path, &header, memory).and_then(|()| fs::rename(&temp_path, path));
+        if result.is_err() {
+            // The temporary file is incomplete, the original error is the one worth reporting
+            let _result = fs::remove_file(&temp_path);
+        }
+        result.map_err(io_error)
+    }
+
+    /// Loads a dataset written by [`RandomXDataset::save_to`], allocating its memory with `flags` like
+    /// [`RandomXDataset::new`].
+    ///
+    /// `RandomXError::DatasetFileMismatch` is returned if the file was written for another key than the one `cache`
+    /// was initialized with, with other RandomX parameters or by an unsupported version of this crate.
+    /// `RandomXError::DatasetFileCorrupt` is returned if the file is truncated or its contents fail the checksum.
+    pub fn load_from<P: AsRef<Path>>(
+        flags: RandomXFlag,
+        cache: RandomXCache,
+        path: P,
+    ) -> Result<RandomXDataset, RandomXError> {
+        let mut file = BufReader::new(File::open(path).map_err(io_error)?);
+        let header = Header::read(&mut file)?;
+        if header.version != VERSION {
+            return Err(RandomXError::DatasetFileMismatch(format!(
+                "unsupported file version {}, expected {VERSION}",
+                header.version
+            )));
+        }
+        let item_count = RandomXDataset::count()?;
+        if header.item_size != u32::try_from(RandomXDataset::ITEM_SIZE)? || header.item_count != item_count {
+            return Err(RandomXError::DatasetFileMismatch(format!(
+                "file has {} items of {} bytes, expected {item_count} items of {} bytes",
+                header.item_count,
+                header.item_size,
+                RandomXDataset::ITEM_SIZE
+            )));
+        }
+        if header.key_fingerprint != key_fingerprint(&cache.inner.key) || header.key != cache.inner.key {
+            return Err(RandomXError::DatasetFileMismatch(
+                "file was written for another key".to_string(),
+            ));
+        }
+
+        if cache.compute_dataset_item(0)? != header.first_item {
+            return Err(RandomXError::DatasetFileMismatch(
+                "file was written with other RandomX parameters".to_string(),
+            ));
+        }
+
+        let dataset = RandomXDataset::alloc(flags, cache, item_count)?;
+        let memory = unsafe { randomx_get_dataset_memory(dataset.inner.dataset_ptr) }.cast::<u8>();
+        if memory.is_null() {
+            return Err(RandomXError::Other("Could not get dataset memory".into()));
+        }
+        // The dataset memory is not initialized yet, so the file is read into `chunk` and copied from there rather
+        // than read into a slice of the dataset memory.
+        let memory_size = RandomXDataset::memory_size()?;
+        let mut chunk = vec![0u8; CHUNK_SIZE];
+        let mut sum = CHECKSUM_SEED;
+        let mut offset = 0;
+        while offset < memory_size {
+            let chunk = &mut chunk[..CHUNK_SIZE.min(memory_size - offset)];
+            file.read_exact(chunk).map_err(read_error)?;
+            sum = checksum(sum, chunk);
+            // SAFETY: The dataset memory is `memory_size` bytes long, so the chunk fits at `offset`, and nothing else
+            // refers to the dataset yet.
+            unsafe { ptr::copy_nonoverlapping(chunk.as_ptr(), memory.add(offset), chunk.len()) };
+            offset += chunk.len();
+        }
+        if sum != header.checksum {
+            return Err(RandomXError::DatasetFileCorrupt("checksum does not match".to_string()));
+        }
+        if file.read(&mut [0u8; 1]).map_err(io_error)? != 0 {
+            return Err(RandomXError::DatasetFileCorrupt(
+                "file is longer than the dataset".to_string(),
+            ));
+        }
+        Ok(dataset)
+    }
+}
+
+/// Writes `header` and `memory` to a new file at `path` and waits until they reach the disk.
+fn write_file(path: &Path, header: &[u8], memory: &[u8]) -> io::Result<()> {
+    let mut file = BufWriter::new(File::create(path)?);
+    file.write_all(header)?;
+    file.write_all(memory)?;
+    file.into_inner()?.sync_all()
+}
+
+#[allow(clippy::needless_pass_by_value)] // Used as a `map_err` argument
+fn io_error(e: io::Error) -> RandomXError {
+    RandomXError::IoError(e.to_string())
+}
+
+#[allow(clippy::needless_pass_by_value)] // Used as a `map_err` argument
+fn read_error(e: io::Error) -> RandomXError {
+    if e.kind() == io::ErrorKind::UnexpectedEof {
+        RandomXError::DatasetFileCorrupt("file is truncated".to_string())
+    } else {
+        io_error(e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        env,
+        fs::{self, OpenOptions},
+        io::{Read, Seek, SeekFrom, Write},
+    };
+
+    use super::{Header, HEADER_SIZE};
+    use crate::{RandomXCache, RandomXDataset, RandomXError, RandomXFlag, RandomXVM};
+
+    #[test]
+    fn dataset_file_header() {
+        let header = Header {
+            version: 1,
+            item_size: 64,
+            item_count: 3,
+            key_fingerprint: 7,
+            first_item: [5; RandomXDataset::ITEM_SIZE],
+            checksum: 9,
+            key: b"Key".to_vec(),
+        };
+        let bytes = header.to_bytes().unwrap();
+        assert_eq!(bytes.len(), HEADER_SIZE + 3);
+        assert_eq!(Header::read(&mut bytes.as_slice()).unwrap(), header);
+        assert!(matches!(
+            Header::read(&mut &bytes[..bytes.len() - 1]),
+            Err(RandomXError::DatasetFileCorrupt(_))
+        ));
+        assert!(matches!(
+            Header::read(&mut &[0; HEADER_SIZE][..]),
+            Err(RandomXError::DatasetFileCorrupt(_))
+        ));
+    }
+
+    #[test]
+    fn dataset_file_round_trip() {
+        let flags = RandomXFlag::get_recommended_flags();
+        let path = env::temp_dir().join(format!("randomx-rs-dataset-{}.bin", std::process::id()));
+        let cache = RandomXCache::new(flags, b"Key").unwrap();
+        let dataset = RandomXDataset::new(flags, cache.clone(), 0).unwrap();
+        dataset.save_to(&path).unwrap();
+        assert!(!path.with_extension("bin.tmp").exists());
+
+        let loaded = RandomXDataset::load_from(flags, cache.clone(), &path).unwrap();
+        assert!(loaded.memory().unwrap() == dataset.memory().unwrap());
+        let hash = |dataset: RandomXDataset| {
+            RandomXVM::new(flags | RandomXFlag::FLAG_FULL_MEM, None, Some(dataset))
+                .unwrap()
+                .calculate_hash(b"Input")
+                .unwrap()
+        };
+        assert_eq!(hash(loaded), hash(dataset));
+
+        let other_cache = RandomXCache::new(flags, b"Other key").unwrap();
+        assert!(matches!(
+            RandomXDataset::load_from(flags, other_cache, &path),
+            Err(RandomXError::DatasetFileMismatch(_))
+        ));
+
+        let mut file = OpenOptions::new().read(true).write(true).open(&path).unwrap();
+        let mut last = [0u8];
+        file.seek(SeekFrom::End(-1)).unwrap();
+        file.read_exact(&mut last).unwrap();
+        file.seek(SeekFrom::End(-1)).unwrap();
+        file.write_all(&[last[0] ^ 1]).unwrap();
+        drop(file);
+        assert!(matches!(
+            RandomXDataset::load_from(flags, cache.clone(), &path),
+            Err(RandomXError::DatasetFileCorrupt(_))
+        ));
+
+        let length = fs::metadata(&path).unwrap().len();
+        OpenOptions::new()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_len(length - 1)
+            .unwrap();
+        assert!(matches!(
+            RandomXDataset::load_from(flags, cache, &path),
+            Err(RandomXError::DatasetFileCorrupt(_))
+        ));
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,260 @@
+// Copyright 2026. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Difficulty and target checks for RandomX hashes.
+//!
+//! Following Monero, a hash is interpreted as a 256-bit little-endian integer and meets a difficulty `d` if
+//! `hash * d < 2^256`. Equivalently, it meets the target `floor((2^256 - 1) / d)` if `hash <= target`. Targets are
+//! represented as 32-byte little-endian integers.
+
+use crate::{bindings::RANDOMX_HASH_SIZE, RandomXHash};
+
+/// Number of 64-bit limbs in a 256-bit integer.
+const LIMBS: usize = RANDOMX_HASH_SIZE as usize / 8;
+
+/// Returns the target a hash must not exceed to meet `difficulty`, as a 32-byte little-endian integer.
+///
+/// A difficulty of 0 is met by every hash, so its target is `2^256 - 1`.
+pub fn difficulty_to_target(difficulty: u128) -> [u8; RANDOMX_HASH_SIZE as usize] {
+    if difficulty == 0 {
+        return [u8::MAX; RANDOMX_HASH_SIZE as usize];
+    }
+    // Long division of 2^256 - 1 by `difficulty`, one bit at a time. The remainder is always less than `difficulty`,
+    // so it only exceeds 128 bits for the moment between the shift and the subtraction.
+    let mut quotient = [0u64; LIMBS];
+    let mut remainder = 0u128;
+    for bit in (0..LIMBS * 64).rev() {
+        let carry = remainder >> 127 == 1;
+        remainder = (remainder << 1) | 1;
+        if carry || remainder >= difficulty {
+            remainder = remainder.wrapping_sub(difficulty);
+            quotient[bit / 64] |= 1 << (bit % 64);
+        }
+    }
+    from_limbs(&quotient)
+}
+
+impl RandomXHash {
+    /// Returns true if the hash meets `difficulty`, i.e. `hash * difficulty < 2^256` with the hash read as a
+    /// little-endian integer.
+    pub fn meets_difficulty(&self, difficulty: u128) -> bool {
+        #[allow(clippy::cast_possible_truncation)] // Splitting into the low and high 64 bits
+        let factors = [difficulty as u64, (difficulty >> 64) as u64];
+        let hash = to_limbs(self.as_bytes());
+        let mut product = [0u64; LIMBS + 2];
+        for (i, factor) in factors.iter().enumerate() {
+            let mut carry = 0u128;
+            for (j, limb) in hash.iter().enumerate() {
+                let value = u128::from(*limb) * u128::from(*factor) + u128::from(product[i + j]) + carry;
+                #[allow(clippy::cast_possible_truncation)] // Keeping the low 64 bits
+                {
+                    product[i + j] = value as u64;
+                }
+                carry = value >> 64;
+            }
+            #[allow(clippy::cast_possible_truncation)] // The carry is less than 2^64
+            {
+                product[i + LIMBS] = carry as u64;
+            }
+        }
+        product[LIMBS..].iter().all(|limb| *limb == 0)
+    }
+
+    /// Returns the highest difficulty this hash meets, saturating at `u128::MAX`.
+    pub fn to_difficulty(&self) -> u128 {
+        if self.meets_difficulty(u128::MAX) {
+            return u128::MAX;
+        }
+        // `meets_difficulty` is monotonic, so search for the boundary. Every hash meets a difficulty of 1.
+        let (mut low, mut high) = (1u128, u128::MAX);
+        while high - low > 1 {
+            let mid = low + (high - low) / 2;
+            if self.meets_difficulty(mid) {
+                low = mid;
+            } else {
+                high = mid;
+            }
+        }
+        low
+    }
+
+    /// Returns true if the hash, read as a little-endian integer, is less than or equal to `target`, which is also
+    /// little-endian.
+    pub fn meets_target(&self, target: &[u8; RANDOMX_HASH_SIZE as usize]) -> bool {
+        self.as_bytes().iter().rev().cmp(target.iter().rev()).is_le()
+    }
+}
+
+fn to_limbs(bytes: &[u8; RANDOMX_HASH_SIZE as usize]) -> [u64; LIMBS] {
+    let mut limbs = [0u64; LIMBS];
+    for (limb, chunk) in limbs.iter_mut().zip(bytes.chunks_exact(8)) {
+        let mut le = [0u8; 8];
+        le.copy_from_slice(chunk);
+        *limb = u64::from_le_bytes(le);
+    }
+    limbs
+}
+
+fn from_limbs(limbs: &[u64; LIMBS]) -> [u8; RANDOMX_HASH_SIZE as usize] {
+    let mut bytes = [0u8; RANDOMX_HASH_SIZE as usize];
+    for (chunk, limb) in bytes.chunks_exact_mut(8).zip(limbs.iter()) {
+        chunk.copy_from_slice(&limb.to_le_bytes());
+    }
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{difficulty_to_target, RandomXHash};
+
+    fn le(value: u128) -> [u8; 32] {
+        let mut bytes = [0u8; 32];
+        bytes[..16].copy_from_slice(&value.to_le_bytes());
+        bytes
+    }
+
+    fn add_one(bytes: [u8; 32]) -> [u8; 32] {
+        let mut bytes = bytes;
+        for byte in &mut bytes {
+            let (value, overflow) = byte.overflowing_add(1);
+            *byte = value;
+            if !overflow {
+                break;
+            }
+        }
+        bytes
+    }
+
+    #[test]
+    fn zero_and_max_hashes() {
+        let zero = RandomXHash::from([0u8; 32]);
+        assert!(zero.meets_difficulty(u128::MAX));
+        assert_eq!(zero.to_difficulty(), u128::MAX);
+
+        let max = RandomXHash::from([u8::MAX; 32]);
+        assert!(max.meets_difficulty(0));
+        assert!(max.meets_difficulty(1));
+        assert!(!max.meets_difficulty(2));
+        assert_eq!(max.to_difficulty(), 1);
+    }
+
+    #[test]
+    fn power_of_two_hashes() {
+        // 2^255 * 2 == 2^256
+        let mut bytes = [0u8; 32];
+        bytes[31] = 0x80;
+        let hash = RandomXHash::from(bytes);
+        assert!(hash.meets_difficulty(1));
+        assert!(!hash.meets_difficulty(2));
+        assert_eq!(hash.to_difficulty(), 1);
+
+        // 2^128 * (2^128 - 1) < 2^256
+        let mut bytes = [0u8; 32];
+        bytes[16] = 1;
+        let hash = RandomXHash::from(bytes);
+        assert!(hash.meets_difficulty(u128::MAX));
+        assert_eq!(hash.to_difficulty(), u128::MAX);
+
+        // 2^192 * 2^64 == 2^256
+        let mut bytes = [0u8; 32];
+        bytes[24] = 1;
+        let hash = RandomXHash::from(bytes);
+        assert!(hash.meets_difficulty(u128::from(u64::MAX)));
+        assert!(!hash.meets_difficulty(1 << 64));
+        assert_eq!(hash.to_difficulty(), u128::from(u64::MAX));
+    }
+
+    #[test]
+    fn difficulty_saturates_at_u128_max() {
+        // (2^128 + 1) * (2^128 - 1) == 2^256 - 1
+        let mut bytes = le(1);
+        bytes[16] = 1;
+        assert_eq!(RandomXHash::from(bytes).to_difficulty(), u128::MAX);
+        // (2^128 + 2) * (2^128 - 1) > 2^256 - 1
+        let mut bytes = le(2);
+        bytes[16] = 1;
+        let hash = RandomXHash::from(bytes);
+        assert!(!hash.meets_difficulty(u128::MAX));
+        assert_eq!(hash.to_difficulty(), u128::MAX - 1);
+
+        assert_eq!(RandomXHash::from(le(1)).to_difficulty(), u128::MAX);
+    }
+
+    #[test]
+    fn target_boundaries() {
+        assert_eq!(difficulty_to_target(0), [u8::MAX; 32]);
+        assert_eq!(difficulty_to_target(1), [u8::MAX; 32]);
+        let mut half = [u8::MAX; 32];
+        half[31] = 0x7f;
+        assert_eq!(difficulty_to_target(2), half);
+        assert_eq!(difficulty_to_target(u128::MAX), {
+            // floor((2^256 - 1) / (2^128 - 1)) == 2^128 + 1
+            let mut bytes = le(1);
+            bytes[16] = 1;
+            bytes
+        });
+
+        let difficulties = [
+            1,
+            2,
+            3,
+            7,
+            1000,
+            u128::from(u32::MAX),
+            u128::from(u64::MAX),
+            1 << 64,
+            (1 << 64) + 1,
+            1 << 127,
+            u128::MAX - 1,
+            u128::MAX,
+        ];
+        for difficulty in difficulties {
+            let target = difficulty_to_target(difficulty);
+            let at_target = RandomXHash::from(target);
+            assert!(at_target.meets_target(&target), "difficulty {}", difficulty);
+            assert!(at_target.meets_difficulty(difficulty), "difficulty {}", difficulty);
+            assert!(at_target.to_difficulty() >= difficulty, "difficulty {}", difficulty);
+            if target != [u8::MAX; 32] {
+                let above_target = RandomXHash::from(add_one(target));
+                assert!(!above_target.meets_target(&target), "difficulty {}", difficulty);
+                assert!(!above_target.meets_difficulty(difficulty), "difficulty {}", difficulty);
+                assert_eq!(
+                    above_target.to_difficulty(),
+                    difficulty - 1,
+                    "difficulty {}",
+                    difficulty
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn target_comparison_is_little_endian() {
+        let mut low = [0u8; 32];
+        low[0] = 0xff;
+        let mut high = [0u8; 32];
+        high[31] = 0x01;
+        assert!(RandomXHash::from(low).meets_target(&high));
+        assert!(!RandomXHash::from(high).meets_target(&low));
+        assert!(RandomXHash::from(high).meets_target(&high));
+    }
+}
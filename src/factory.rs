@@ -0,0 +1,401 @@
+// Copyright 2026. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::{
+    ops::{Deref, DerefMut},
+    sync::{Arc, Condvar, Mutex, MutexGuard},
+};
+
+use crate::{RandomXCache, RandomXError, RandomXFlag, RandomXVM};
+
+#[derive(Debug)]
+struct KeyEntry {
+    key: Vec<u8>,
+    // Filled by the first thread that needs the cache; others wait on the mutex instead of building their own.
+    cache: Arc<Mutex<Option<RandomXCache>>>,
+    idle: Vec<RandomXVM>,
+    in_use: usize,
+    last_used: u64,
+}
+
+#[derive(Debug, Default)]
+struct FactoryState {
+    keys: Vec<KeyEntry>,
+    vm_count: usize,
+    clock: u64,
+}
+
+impl FactoryState {
+    fn position(&self, key: &[u8]) -> Option<usize> {
+        self.keys.iter().position(|entry| entry.key == key)
+    }
+
+    /// Returns true if `key` is held, or can be added without exceeding `max_keys` after evicting a key.
+    fn has_key_room(&self, key: &[u8], max_keys: usize) -> bool {
+        self.position(key).is_some() || self.keys.len() < max_keys || self.keys.iter().any(|entry| entry.in_use == 0)
+    }
+
+    /// Returns true if a VM can be created without exceeding `max_vms` after destroying an idle VM.
+    fn has_vm_room(&self, max_vms: usize) -> bool {
+        self.vm_count < max_vms || self.keys.iter().any(|entry| !entry.idle.is_empty())
+    }
+
+    /// Destroys an idle VM of the least recently used key, returning false if every VM is in use.
+    fn evict_idle_vm(&mut self) -> bool {
+        let entry = self
+            .keys
+            .iter_mut()
+            .filter(|entry| !entry.idle.is_empty())
+            .min_by_key(|entry| entry.last_used);
+        match entry {
+            Some(entry) => {
+                entry.idle.pop();
+                self.vm_count -= 1;
+                true
+            },
+            None => false,
+        }
+    }
+
+    /// Drops the least recently used key that has no VMs in use, returning false if there is none.
+    fn evict_key(&mut self) -> bool {
+        let index = self
+            .keys
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| entry.in_use == 0)
+            .min_by_key(|(_, entry)| entry.last_used)
+            .map(|(index, _)| index);
+        match index {
+            Some(index) => {
+                let entry = self.keys.swap_remove(index);
+                self.vm_count -= entry.idle.len();
+                true
+            },
+            None => false,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct FactoryInner {
+    flags: RandomXFlag,
+    max_keys: usize,
+    max_vms: usize,
+    state: Mutex<FactoryState>,
+    released: Condvar,
+}
+
+/// A pool of light-mode VMs indexed by key, shared across threads.
+///
+/// The cache of a key is created the first time a VM for it is requested and shared by every VM of that key. Once
+/// `max_keys` keys are held, the least recently used key without VMs in use is dropped to make room. At most
+/// `max_vms` VMs exist at any time; idle VMs of other keys are destroyed before a request has to wait.
+///
+/// Cloning the factory is cheap and every clone shares the same pool.
+#[derive(Debug, Clone)]
+pub struct RandomXFactory {
+    inner: Arc<FactoryInner>,
+}
+
+impl RandomXFactory {
+    /// Creates an empty factory. `flags` must not contain FLAG_FULL_MEM.
+    pub fn new(flags: RandomXFlag, max_keys: usize, max_vms: usize) -> Result<Self, RandomXError> {
+        if flags.contains(RandomXFlag::FLAG_FULL_MEM) {
+            return Err(RandomXError::FlagConfigError(
+                "RandomXFactory only creates light-mode VMs".to_string(),
+            ));
+        }
+        if max_keys == 0 || max_vms == 0 {
+            return Err(RandomXError::ParameterError(
+                "max_keys and max_vms must be at least 1".to_string(),
+            ));
+        }
+        Ok(Self {
+            inner: Arc::new(FactoryInner {
+                flags,
+                max_keys,
+                max_vms,
+                state: Mutex::new(FactoryState::default()),
+                released: Condvar::new(),
+            }),
+        })
+    }
+
+    /// Returns a VM for `key`, waiting for another VM to be released if the limits are reached.
+    ///
+    /// A thread that already holds `max_vms` VMs of this factory will wait forever.
+    pub fn get_vm(&self, key: &[u8]) -> Result<PooledVM, RandomXError> {
+        self.acquire(key, true)
+    }
+
+    /// Returns a VM for `key`, or `RandomXError::PoolExhausted` if the limits are reached.
+    pub fn try_get_vm(&self, key: &[u8]) -> Result<PooledVM, RandomXError> {
+        self.acquire(key, false)
+    }
+
+    /// Returns the number of keys currently held.
+    pub fn key_count(&self) -> Result<usize, RandomXError> {
+        Ok(lock(&self.inner.state)?.keys.len())
+    }
+
+    /// Returns the number of VMs currently alive, both idle and in use.
+    pub fn vm_count(&self) -> Result<usize, RandomXError> {
+        Ok(lock(&self.inner.state)?.vm_count)
+    }
+
+    fn acquire(&self, key: &[u8], block: bool) -> Result<PooledVM, RandomXError> {
+        if key.is_empty() {
+            return Err(RandomXError::ParameterError("key is empty".to_string()));
+        }
+        let mut state = lock(&self.inner.state)?;
+        let cache = loop {
+            state.clock += 1;
+            let now = state.clock;
+            if let Some(index) = state.position(key) {
+                let entry = &mut state.keys[index];
+                entry.last_used = now;
+                if let Some(vm) = entry.idle.pop() {
+                    entry.in_use += 1;
+                    return Ok(self.guard(key, vm));
+                }
+            }
+
+            // Nothing is evicted unless both a key and a VM slot can be had, so a failed request leaves the pool as
+            // it was
+            if state.has_key_room(key, self.inner.max_keys) && state.has_vm_room(self.inner.max_vms) {
+                let index = match state.position(key) {
+                    Some(index) => index,
+                    None => {
+                        if state.keys.len() >= self.inner.max_keys {
+                            state.evict_key();
+                        }
+                        state.keys.push(KeyEntry {
+                            key: key.to_vec(),
+                            cache: Arc::new(Mutex::new(None)),
+                            idle: Vec::new(),
+                            in_use: 0,
+                            last_used: now,
+                        });
+                        state.keys.len() - 1
+                    },
+                };
+                // Evicting a key may have destroyed its idle VMs and freed a slot already
+                if state.vm_count >= self.inner.max_vms {
+                    state.evict_idle_vm();
+                }
+                // Reserve the VM slot so other threads see it while the VM is being created
+                state.vm_count += 1;
+                state.keys[index].in_use += 1;
+                break state.keys[index].cache.clone();
+            }
+
+            if !block {
+                return Err(RandomXError::PoolExhausted(format!(
+                    "all {} VMs or {} keys are in use",
+                    self.inner.max_vms, self.inner.max_keys
+                )));
+            }
+            state = self
+                .inner
+                .released
+                .wait(state)
+                .map_err(|_| RandomXError::Other("Lock poisoned".to_string()))?;
+        };
+        drop(state);
+
+        match self.create_vm(key, &cache) {
+            Ok(vm) => Ok(self.guard(key, vm)),
+            Err(e) => {
+                let mut state = lock(&self.inner.state)?;
+                state.vm_count -= 1;
+                if let Some(index) = state.position(key) {
+                    state.keys[index].in_use -= 1;
+                }
+                self.inner.released.notify_all();
+                Err(e)
+            },
+        }
+    }
+
+    fn create_vm(&self, key: &[u8], cache: &Mutex<Option<RandomXCache>>) -> Result<RandomXVM, RandomXError> {
+        let cache = {
+            let mut cache = lock(cache)?;
+            match &*cache {
+                Some(cache) => cache.clone(),
+                None => cache.insert(RandomXCache::new(self.inner.flags, key)?).clone(),
+            }
+        };
+        RandomXVM::new(self.inner.flags, Some(cache), None)
+    }
+
+    fn guard(&self, key: &[u8], vm: RandomXVM) -> PooledVM {
+        PooledVM {
+            factory: self.inner.clone(),
+            key: key.to_vec(),
+            vm: Some(vm),
+        }
+    }
+}
+
+/// A VM borrowed from a [`RandomXFactory`], returned to the pool when dropped.
+#[derive(Debug)]
+pub struct PooledVM {
+    factory: Arc<FactoryInner>,
+    key: Vec<u8>,
+    vm: Option<RandomXVM>,
+}
+
+impl PooledVM {
+    /// Returns the key the VM was initialized with.
+    pub fn key(&self) -> &[u8] {
+        &self.key
+    }
+}
+
+impl Deref for PooledVM {
+    type Target = RandomXVM;
+
+    fn deref(&self) -> &RandomXVM {
+        self.vm.as_ref().expect("VM is only taken on drop")
+    }
+}
+
+impl DerefMut for PooledVM {
+    fn deref_mut(&mut self) -> &mut RandomXVM {
+        self.vm.as_mut().expect("VM is only taken on drop")
+    }
+}
+
+impl Drop for PooledVM {
+    fn drop(&mut self) {
+        let mut state = match self.factory.state.lock() {
+            Ok(state) => state,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        // Keys with VMs in use are never evicted, so the entry is still there
+        if let Some(index) = state.position(&self.key) {
+            let entry = &mut state.keys[index];
+            entry.in_use -= 1;
+            entry.idle.extend(self.vm.take());
+        }
+        self.factory.released.notify_all();
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> Result<MutexGuard<'_, T>, RandomXError> {
+    mutex
+        .lock()
+        .map_err(|_| RandomXError::Other("Lock poisoned".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::mpsc, thread, time::Duration};
+
+    use crate::{RandomXCache, RandomXError, RandomXFactory, RandomXFlag, RandomXVM};
+
+    #[test]
+    fn factory_hashes_with_the_requested_key() {
+        let flags = RandomXFlag::get_recommended_flags();
+        let factory = RandomXFactory::new(flags, 2, 2).unwrap();
+        assert!(RandomXFactory::new(flags, 0, 1).is_err());
+        assert!(RandomXFactory::new(flags | RandomXFlag::FLAG_FULL_MEM, 1, 1).is_err());
+        assert!(factory.get_vm(b"").is_err());
+
+        let cache = RandomXCache::new(flags, b"Key 1").unwrap();
+        let expected = RandomXVM::new(flags, Some(cache), None)
+            .unwrap()
+            .calculate_hash(b"Input")
+            .unwrap();
+        let vm = factory.get_vm(b"Key 1").unwrap();
+        assert_eq!(vm.key(), b"Key 1");
+        assert_eq!(vm.calculate_hash(b"Input").unwrap(), expected);
+        drop(vm);
+
+        // The idle VM is reused rather than a new one created
+        let _vm = factory.get_vm(b"Key 1").unwrap();
+        assert_eq!(factory.vm_count().unwrap(), 1);
+        assert_eq!(factory.key_count().unwrap(), 1);
+    }
+
+    #[test]
+    fn factory_failed_request_keeps_keys() {
+        let flags = RandomXFlag::get_recommended_flags();
+        let factory = RandomXFactory::new(flags, 2, 1).unwrap();
+
+        let vm_1 = factory.get_vm(b"Key 1").unwrap();
+        // No VM slot is free, so neither request may add or evict a key
+        for key in [&b"Key 2"[..], b"Key 3"] {
+            assert!(matches!(factory.try_get_vm(key), Err(RandomXError::PoolExhausted(_))));
+            assert_eq!(factory.key_count().unwrap(), 1);
+        }
+        drop(vm_1);
+
+        // The idle VM of Key 1 is destroyed to make room, but Key 1 keeps its cache
+        let vm_2 = factory.try_get_vm(b"Key 2").unwrap();
+        assert_eq!(factory.key_count().unwrap(), 2);
+        assert_eq!(factory.vm_count().unwrap(), 1);
+        drop(vm_2);
+    }
+
+    #[test]
+    fn factory_limits_and_eviction() {
+        let flags = RandomXFlag::get_recommended_flags();
+        let factory = RandomXFactory::new(flags, 2, 2).unwrap();
+
+        let vm_1 = factory.get_vm(b"Key 1").unwrap();
+        let vm_2 = factory.get_vm(b"Key 2").unwrap();
+        assert!(matches!(
+            factory.try_get_vm(b"Key 1"),
+            Err(RandomXError::PoolExhausted(_))
+        ));
+        assert!(matches!(
+            factory.try_get_vm(b"Key 3"),
+            Err(RandomXError::PoolExhausted(_))
+        ));
+        assert_eq!(factory.key_count().unwrap(), 2);
+
+        // Key 1 is least recently used, so it is evicted once its VM is released
+        drop(vm_1);
+        let vm_3 = factory.try_get_vm(b"Key 3").unwrap();
+        assert_eq!(factory.key_count().unwrap(), 2);
+        assert_eq!(factory.vm_count().unwrap(), 2);
+
+        // A blocked request proceeds when a VM is released on another thread
+        let (sender, receiver) = mpsc::channel();
+        let waiter = {
+            let factory = factory.clone();
+            thread::spawn(move || {
+                let vm = factory.get_vm(b"Key 3").unwrap();
+                sender.send(()).unwrap();
+                vm.calculate_hash(b"Input").unwrap()
+            })
+        };
+        assert!(receiver.recv_timeout(Duration::from_millis(200)).is_err());
+        drop(vm_3);
+        assert!(receiver.recv_timeout(Duration::from_secs(60)).is_ok());
+        drop(vm_2);
+        waiter.join().unwrap();
+        assert_eq!(factory.vm_count().unwrap(), 2);
+    }
+}
@@ -0,0 +1,280 @@
+// Copyright 2026. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Cheap stand-ins for the RandomX types, enabled by the `test-fake` feature, for unit tests that exercise code
+//! using this crate without paying for real caches and hashes.
+
+use std::sync::Arc;
+
+use blake2::{digest::consts::U32, Blake2b, Digest};
+
+use crate::{RandomXBackend, RandomXError, RandomXFlag, RandomXHash};
+
+/// A stand-in for [`RandomXCache`](crate::RandomXCache) that only keeps the key.
+#[derive(Debug, Clone)]
+pub struct FakeRandomXCache {
+    key: Arc<[u8]>,
+}
+
+impl FakeRandomXCache {
+    /// Creates a cache for `key`. Fails like [`RandomXCache::new`](crate::RandomXCache::new) if `key` is empty and
+    /// ignores `flags`.
+    pub fn new(_flags: RandomXFlag, key: &[u8]) -> Result<FakeRandomXCache, RandomXError> {
+        if key.is_empty() {
+            return Err(RandomXError::ParameterError("key is empty".to_string()));
+        }
+        Ok(FakeRandomXCache { key: key.into() })
+    }
+}
+
+/// A stand-in for [`RandomXDataset`](crate::RandomXDataset) that allocates nothing.
+#[derive(Debug, Clone)]
+pub struct FakeRandomXDataset {
+    cache: FakeRandomXCache,
+}
+
+impl FakeRandomXDataset {
+    /// Creates a dataset from `cache`, ignoring `flags` and `start`.
+    pub fn new(_flags: RandomXFlag, cache: FakeRandomXCache, _start: u32) -> Result<FakeRandomXDataset, RandomXError> {
+        Ok(FakeRandomXDataset { cache })
+    }
+}
+
+/// A stand-in for [`RandomXVM`](crate::RandomXVM) whose hash is the Blake2b-256 of the key length (as a
+/// little-endian u64), the key and the input. It is deterministic and fast, but has nothing to do with RandomX.
+#[derive(Debug, Clone)]
+pub struct FakeRandomXVM {
+    flags: RandomXFlag,
+    cache: Option<FakeRandomXCache>,
+    dataset: Option<FakeRandomXDataset>,
+    meets_target: bool,
+}
+
+impl FakeRandomXVM {
+    /// Creates a VM like [`RandomXVM::new`](crate::RandomXVM::new): it needs `dataset` if `flags` contains
+    /// FLAG_FULL_MEM, and `cache` otherwise.
+    pub fn new(
+        flags: RandomXFlag,
+        cache: Option<FakeRandomXCache>,
+        dataset: Option<FakeRandomXDataset>,
+    ) -> Result<FakeRandomXVM, RandomXError> {
+        let full_mem = flags.contains(RandomXFlag::FLAG_FULL_MEM);
+        if (full_mem && dataset.is_none()) || (!full_mem && cache.is_none()) {
+            return Err(RandomXError::CreationError("Failed to allocate VM".to_string()));
+        }
+        Ok(FakeRandomXVM {
+            flags,
+            cache,
+            dataset,
+            meets_target: false,
+        })
+    }
+
+    /// Returns the flags the `VM` was created with.
+    pub fn flags(&self) -> RandomXFlag {
+        self.flags
+    }
+
+    /// If `meets_target` is true, every hash is zero, so it meets any target or difficulty. Otherwise hashes are
+    /// computed normally.
+    pub fn set_meets_target(&mut self, meets_target: bool) {
+        self.meets_target = meets_target;
+    }
+
+    /// Replaces the cache, error if the `VM` was created with FLAG_FULL_MEM.
+    pub fn reinit_cache(&mut self, cache: FakeRandomXCache) -> Result<(), RandomXError> {
+        if self.flags.contains(RandomXFlag::FLAG_FULL_MEM) {
+            return Err(RandomXError::FlagConfigError(
+                "Cannot reinit cache with FLAG_FULL_MEM set".to_string(),
+            ));
+        }
+        self.cache = Some(cache);
+        Ok(())
+    }
+
+    /// Replaces the dataset, error if the `VM` was created without FLAG_FULL_MEM.
+    pub fn reinit_dataset(&mut self, dataset: FakeRandomXDataset) -> Result<(), RandomXError> {
+        if !self.flags.contains(RandomXFlag::FLAG_FULL_MEM) {
+            return Err(RandomXError::FlagConfigError(
+                "Cannot reinit dataset without FLAG_FULL_MEM set".to_string(),
+            ));
+        }
+        self.dataset = Some(dataset);
+        Ok(())
+    }
+
+    /// Calculates the fake hash of `input`, error if it is empty.
+    pub fn calculate_hash(&self, input: &[u8]) -> Result<RandomXHash, RandomXError> {
+        if input.is_empty() {
+            return Err(RandomXError::ParameterError("input was empty".to_string()));
+        }
+        if self.meets_target {
+            return Ok(RandomXHash::default());
+        }
+        let key = match (&self.dataset, &self.cache) {
+            (Some(dataset), _) if self.flags.contains(RandomXFlag::FLAG_FULL_MEM) => &dataset.cache.key,
+            (_, Some(cache)) => &cache.key,
+            _ => return Err(RandomXError::Other("VM has no cache or dataset".to_string())),
+        };
+        let digest = Blake2b::<U32>::new()
+            .chain_update((key.len() as u64).to_le_bytes())
+            .chain_update(key)
+            .chain_update(input)
+            .finalize();
+        let mut hash = [0u8; 32];
+        hash.copy_from_slice(&digest);
+        Ok(RandomXHash::from(hash))
+    }
+
+    /// Calculates the fake hashes of `inputs`, error if the set or any input is empty.
+    pub fn calculate_hash_set(&self, inputs: &[&[u8]]) -> Result<Vec<RandomXHash>, RandomXError> {
+        if inputs.is_empty() {
+            return Err(RandomXError::ParameterError("input was empty".to_string()));
+        }
+        inputs.iter().map(|input| self.calculate_hash(input)).collect()
+    }
+}
+
+/// The fake types as a [`RandomXBackend`], for code that is generic over the backend.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FakeBackend {
+    meets_target: bool,
+}
+
+impl FakeBackend {
+    /// Creates a backend whose VMs compute fake hashes.
+    pub fn new() -> FakeBackend {
+        FakeBackend::default()
+    }
+
+    /// Creates a backend whose VMs always return the zero hash, see [`FakeRandomXVM::set_meets_target`].
+    pub fn meeting_target() -> FakeBackend {
+        FakeBackend { meets_target: true }
+    }
+}
+
+impl RandomXBackend for FakeBackend {
+    type Cache = FakeRandomXCache;
+    type Dataset = FakeRandomXDataset;
+    type VM = FakeRandomXVM;
+
+    fn supports_full_mem(&self) -> bool {
+        true
+    }
+
+    fn create_cache(&self, flags: RandomXFlag, key: &[u8]) -> Result<FakeRandomXCache, RandomXError> {
+        FakeRandomXCache::new(flags, key)
+    }
+
+    fn create_dataset(
+        &self,
+        flags: RandomXFlag,
+        cache: FakeRandomXCache,
+        start: u32,
+    ) -> Result<FakeRandomXDataset, RandomXError> {
+        FakeRandomXDataset::new(flags, cache, start)
+    }
+
+    fn create_vm(
+        &self,
+        flags: RandomXFlag,
+        cache: Option<FakeRandomXCache>,
+        dataset: Option<FakeRandomXDataset>,
+    ) -> Result<FakeRandomXVM, RandomXError> {
+        let mut vm = FakeRandomXVM::new(flags, cache, dataset)?;
+        vm.set_meets_target(self.meets_target);
+        Ok(vm)
+    }
+
+    fn calculate_hash(&self, vm: &FakeRandomXVM, input: &[u8]) -> Result<RandomXHash, RandomXError> {
+        vm.calculate_hash(input)
+    }
+
+    fn calculate_hash_set(&self, vm: &FakeRandomXVM, inputs: &[&[u8]]) -> Result<Vec<RandomXHash>, RandomXError> {
+        vm.calculate_hash_set(inputs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fake_hash_is_deterministic_and_keyed() {
+        let flags = RandomXFlag::default();
+        let cache = FakeRandomXCache::new(flags, b"Key").unwrap();
+        let vm = FakeRandomXVM::new(flags, Some(cache.clone()), None).unwrap();
+        let hash = vm.calculate_hash(b"Input").unwrap();
+        assert_ne!(hash, RandomXHash::default());
+        assert_eq!(hash, vm.calculate_hash(b"Input").unwrap());
+        assert_ne!(hash, vm.calculate_hash(b"Input 2").unwrap());
+
+        let other_key = FakeRandomXVM::new(flags, Some(FakeRandomXCache::new(flags, b"Key2").unwrap()), None).unwrap();
+        assert_ne!(hash, other_key.calculate_hash(b"Input").unwrap());
+        // the key length is hashed, so moving bytes between key and input changes the hash
+        let shifted = FakeRandomXVM::new(flags, Some(FakeRandomXCache::new(flags, b"KeyI").unwrap()), None).unwrap();
+        assert_ne!(hash, shifted.calculate_hash(b"nput").unwrap());
+
+        let full_mem = flags | RandomXFlag::FLAG_FULL_MEM;
+        let dataset = FakeRandomXDataset::new(full_mem, cache, 0).unwrap();
+        let fast_vm = FakeRandomXVM::new(full_mem, None, Some(dataset)).unwrap();
+        assert_eq!(hash, fast_vm.calculate_hash(b"Input").unwrap());
+
+        let inputs = [&b"Input"[..], &b"Input 2"[..]];
+        assert_eq!(vm.calculate_hash_set(&inputs).unwrap(), vec![
+            hash,
+            vm.calculate_hash(b"Input 2").unwrap()
+        ]);
+    }
+
+    #[test]
+    fn fake_meets_target_switch() {
+        let flags = RandomXFlag::default();
+        let cache = FakeRandomXCache::new(flags, b"Key").unwrap();
+        let mut vm = FakeRandomXVM::new(flags, Some(cache.clone()), None).unwrap();
+        assert!(!vm.calculate_hash(b"Input").unwrap().meets_difficulty(u128::MAX));
+        vm.set_meets_target(true);
+        assert!(vm.calculate_hash(b"Input").unwrap().meets_difficulty(u128::MAX));
+        vm.set_meets_target(false);
+        assert!(!vm.calculate_hash(b"Input").unwrap().meets_difficulty(u128::MAX));
+
+        let backend = FakeBackend::meeting_target();
+        let vm = backend.create_vm(flags, Some(cache), None).unwrap();
+        assert!(backend.calculate_hash(&vm, b"Input").unwrap().meets_target(&[0; 32]));
+    }
+
+    #[test]
+    fn fake_mirrors_real_errors() {
+        let flags = RandomXFlag::default();
+        assert!(FakeRandomXCache::new(flags, b"").is_err());
+        assert!(FakeRandomXVM::new(flags, None, None).is_err());
+        assert!(FakeRandomXVM::new(flags | RandomXFlag::FLAG_FULL_MEM, None, None).is_err());
+
+        let cache = FakeRandomXCache::new(flags, b"Key").unwrap();
+        let mut vm = FakeRandomXVM::new(flags, Some(cache.clone()), None).unwrap();
+        assert!(vm.calculate_hash(b"").is_err());
+        assert!(vm.calculate_hash_set(&[]).is_err());
+        assert!(vm.reinit_cache(cache.clone()).is_ok());
+        let dataset = FakeRandomXDataset::new(flags, cache, 0).unwrap();
+        assert!(vm.reinit_dataset(dataset).is_err());
+    }
+}
@@ -0,0 +1,185 @@
+// Copyright 2026. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::{
+    convert::TryFrom,
+    fmt::{Debug, Display, Formatter},
+    str::FromStr,
+};
+
+use crate::{bindings::RANDOMX_HASH_SIZE, RandomXError};
+
+/// A RandomX hash value, as calculated by a [`RandomXVM`](crate::RandomXVM).
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RandomXHash([u8; RANDOMX_HASH_SIZE as usize]);
+
+impl RandomXHash {
+    /// Returns the hash as an array of bytes.
+    pub fn as_bytes(&self) -> &[u8; RANDOMX_HASH_SIZE as usize] {
+        &self.0
+    }
+
+    /// Returns the hash as an array of bytes, consuming the hash.
+    pub fn into_bytes(self) -> [u8; RANDOMX_HASH_SIZE as usize] {
+        self.0
+    }
+
+    /// Returns the hash as a lowercase hex string.
+    pub fn to_hex(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl From<[u8; RANDOMX_HASH_SIZE as usize]> for RandomXHash {
+    fn from(bytes: [u8; RANDOMX_HASH_SIZE as usize]) -> Self {
+        RandomXHash(bytes)
+    }
+}
+
+impl From<RandomXHash> for [u8; RANDOMX_HASH_SIZE as usize] {
+    fn from(hash: RandomXHash) -> Self {
+        hash.0
+    }
+}
+
+impl TryFrom<&[u8]> for RandomXHash {
+    type Error = RandomXError;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        <[u8; RANDOMX_HASH_SIZE as usize]>::try_from(bytes)
+            .map(RandomXHash)
+            .map_err(|_| {
+                RandomXError::ParameterError(format!(
+                    "hash must be {RANDOMX_HASH_SIZE} bytes, got {} bytes",
+                    bytes.len()
+                ))
+            })
+    }
+}
+
+impl AsRef<[u8]> for RandomXHash {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl Display for RandomXHash {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for byte in &self.0 {
+            write!(f, "{byte:02x}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Debug for RandomXHash {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "RandomXHash({self})")
+    }
+}
+
+impl FromStr for RandomXHash {
+    type Err = RandomXError;
+
+    /// Parses a hash from a hex string of exactly `2 * RANDOMX_HASH_SIZE` characters.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.len() != 2 * RANDOMX_HASH_SIZE as usize {
+            return Err(RandomXError::ParameterError(format!(
+                "hex hash must be {} characters, got {} characters",
+                2 * RANDOMX_HASH_SIZE,
+                s.len()
+            )));
+        }
+        if !s.bytes().all(|c| c.is_ascii_hexdigit()) {
+            return Err(RandomXError::ParameterError(format!("invalid hex digits in hash: {s}")));
+        }
+        let mut bytes = [0u8; RANDOMX_HASH_SIZE as usize];
+        for (byte, pair) in bytes.iter_mut().zip(s.as_bytes().chunks_exact(2)) {
+            // Every pair is two ASCII hex digits, so neither conversion can fail
+            let pair = std::str::from_utf8(pair).expect("hex digits are ASCII");
+            *byte = u8::from_str_radix(pair, 16).expect("hex digits are valid");
+        }
+        Ok(RandomXHash(bytes))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashSet, convert::TryFrom};
+
+    use crate::RandomXHash;
+
+    #[test]
+    fn hash_hex_round_trip() {
+        let hex = "639183aae1bf4c9a35884cb46b09cad9175f04efd7684e7262a0ac1c2f0b4e3f";
+        let hash = hex.parse::<RandomXHash>().unwrap();
+        assert_eq!(hash.as_ref(), hex::decode(hex).unwrap().as_slice());
+        assert_eq!(hash.to_string(), hex);
+        assert_eq!(hash.to_hex(), hex);
+        assert_eq!(format!("{hash:?}"), format!("RandomXHash({hex})"));
+        assert_eq!(hex.to_uppercase().parse::<RandomXHash>().unwrap(), hash);
+    }
+
+    #[test]
+    fn hash_from_str_rejects_invalid_input() {
+        assert!("".parse::<RandomXHash>().is_err());
+        assert!("00".parse::<RandomXHash>().is_err());
+        assert!("639183aae1bf4c9a35884cb46b09cad9175f04efd7684e7262a0ac1c2f0b4e3f00"
+            .parse::<RandomXHash>()
+            .is_err());
+        assert!("g39183aae1bf4c9a35884cb46b09cad9175f04efd7684e7262a0ac1c2f0b4e3f"
+            .parse::<RandomXHash>()
+            .is_err());
+        assert!("+39183aae1bf4c9a35884cb46b09cad9175f04efd7684e7262a0ac1c2f0b4e3f"
+            .parse::<RandomXHash>()
+            .is_err());
+        assert!("é9183aae1bf4c9a35884cb46b09cad9175f04efd7684e7262a0ac1c2f0b4e3"
+            .parse::<RandomXHash>()
+            .is_err());
+    }
+
+    #[test]
+    fn hash_conversions() {
+        let mut bytes = [0u8; 32];
+        bytes[0] = 1;
+        bytes[31] = 2;
+        let hash = RandomXHash::from(bytes);
+        assert_eq!(hash.as_bytes(), &bytes);
+        assert_eq!(<[u8; 32]>::from(hash), bytes);
+        assert_eq!(hash.into_bytes(), bytes);
+        assert_eq!(RandomXHash::try_from(&bytes[..]).unwrap(), hash);
+        assert!(RandomXHash::try_from(&bytes[..31]).is_err());
+    }
+
+    #[test]
+    fn hash_ordering_and_hashing() {
+        let low = RandomXHash::from([0u8; 32]);
+        let mut bytes = [0u8; 32];
+        bytes[31] = 1;
+        let high = RandomXHash::from(bytes);
+        assert!(low < high);
+        assert_eq!(low, RandomXHash::default());
+
+        let set = [low, high, low].iter().copied().collect::<HashSet<_>>();
+        assert_eq!(set.len(), 2);
+    }
+}
@@ -0,0 +1,190 @@
+// Copyright 2026. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Large page availability on Linux, read from `/proc/meminfo` and `/sys/kernel/mm/hugepages`.
+
+use std::{convert::TryFrom, fs, path::Path};
+
+use crate::{RandomXCache, RandomXDataset, RandomXError};
+
+const MEMINFO: &str = "/proc/meminfo";
+const HUGEPAGES_DIR: &str = "/sys/kernel/mm/hugepages";
+const PAGE_SIZE_2MIB: u64 = 2 * 1024 * 1024;
+const PAGE_SIZE_1GIB: u64 = 1024 * 1024 * 1024;
+
+/// The huge pages that are currently free on this host.
+///
+/// RandomX allocates large pages of the default huge page size, so only `free_default_pages` count towards
+/// FLAG_LARGE_PAGES allocations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HugePageInfo {
+    /// The default huge page size in bytes.
+    pub default_page_size: u64,
+    /// The number of free huge pages of the default size.
+    pub free_default_pages: u64,
+    /// The number of free 2 MiB huge pages.
+    pub free_2mib_pages: u64,
+    /// The number of free 1 GiB huge pages.
+    pub free_1gib_pages: u64,
+}
+
+impl HugePageInfo {
+    /// Reads the huge page configuration of this host, error if `/proc/meminfo` cannot be read or does not report
+    /// huge pages.
+    pub fn probe() -> Result<HugePageInfo, RandomXError> {
+        let meminfo =
+            fs::read_to_string(MEMINFO).map_err(|e| RandomXError::Other(format!("Could not read {MEMINFO}: {e}")))?;
+        let dir = Path::new(HUGEPAGES_DIR);
+        HugePageInfo::parse(
+            &meminfo,
+            read_free_pages(&dir.join("hugepages-2048kB/free_hugepages")),
+            read_free_pages(&dir.join("hugepages-1048576kB/free_hugepages")),
+        )
+    }
+
+    #[allow(clippy::similar_names)] // Named after the fields they fill
+    fn parse(meminfo: &str, free_2mib_pages: u64, free_1gib_pages: u64) -> Result<HugePageInfo, RandomXError> {
+        let default_page_size = meminfo_value(meminfo, "Hugepagesize:")
+            .ok_or_else(|| RandomXError::Other("Hugepagesize not found in meminfo".to_string()))?
+            .checked_mul(1024)
+            .ok_or_else(|| RandomXError::Other("Hugepagesize overflows u64".to_string()))?;
+        let free_default_pages = meminfo_value(meminfo, "HugePages_Free:")
+            .ok_or_else(|| RandomXError::Other("HugePages_Free not found in meminfo".to_string()))?;
+        Ok(HugePageInfo {
+            default_page_size,
+            free_default_pages,
+            free_2mib_pages,
+            free_1gib_pages,
+        })
+    }
+
+    /// Returns the number of default-size huge pages needed to allocate the cache and, if `full_mem` is set, the
+    /// dataset in large pages.
+    ///
+    /// Each VM created with FLAG_LARGE_PAGES needs another 2 MiB for its scratchpad, which is not included.
+    #[allow(unknown_lints, clippy::manual_div_ceil)] // div_ceil needs Rust 1.73
+    pub fn required_pages(&self, full_mem: bool) -> Result<u64, RandomXError> {
+        if self.default_page_size == 0 {
+            return Err(RandomXError::Other("Huge page size is 0".to_string()));
+        }
+        let mut sizes = vec![u64::try_from(RandomXCache::MEMORY_SIZE)?];
+        if full_mem {
+            sizes.push(u64::try_from(RandomXDataset::memory_size()?)?);
+        }
+        let page = self.default_page_size;
+        Ok(sizes.iter().map(|size| (size + page - 1) / page).sum())
+    }
+
+    /// Returns true if there are enough free huge pages to allocate the cache and, if `full_mem` is set, the dataset
+    /// in large pages.
+    pub fn can_allocate(&self, full_mem: bool) -> Result<bool, RandomXError> {
+        Ok(self.free_default_pages >= self.required_pages(full_mem)?)
+    }
+
+    /// Returns the number of free bytes in 2 MiB huge pages.
+    pub fn free_2mib_bytes(&self) -> u64 {
+        self.free_2mib_pages.saturating_mul(PAGE_SIZE_2MIB)
+    }
+
+    /// Returns the number of free bytes in 1 GiB huge pages.
+    pub fn free_1gib_bytes(&self) -> u64 {
+        self.free_1gib_pages.saturating_mul(PAGE_SIZE_1GIB)
+    }
+}
+
+/// Returns the first number after `key` in `meminfo`, ignoring any unit.
+pub(crate) fn meminfo_value(meminfo: &str, key: &str) -> Option<u64> {
+    meminfo
+        .lines()
+        .find_map(|line| line.strip_prefix(key))
+        .and_then(|rest| rest.split_whitespace().next())
+        .and_then(|value| value.parse().ok())
+}
+
+/// Returns the number in a sysfs `free_hugepages` file, or 0 if the page size is not supported.
+fn read_free_pages(path: &Path) -> u64 {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|value| value.trim().parse().ok())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{HugePageInfo, PAGE_SIZE_2MIB};
+
+    const MEMINFO: &str = "MemTotal:       32596288 kB
+MemFree:         1270160 kB
+HugePages_Total:    1280
+HugePages_Free:     1200
+HugePages_Rsvd:        0
+HugePages_Surp:        0
+Hugepagesize:       2048 kB
+Hugetlb:         2621440 kB
+";
+
+    #[test]
+    fn parse_meminfo() {
+        let info = HugePageInfo::parse(MEMINFO, 1200, 1).unwrap();
+        assert_eq!(info, HugePageInfo {
+            default_page_size: PAGE_SIZE_2MIB,
+            free_default_pages: 1200,
+            free_2mib_pages: 1200,
+            free_1gib_pages: 1,
+        });
+        assert_eq!(info.free_2mib_bytes(), 1200 * PAGE_SIZE_2MIB);
+        assert_eq!(info.free_1gib_bytes(), 1024 * 1024 * 1024);
+
+        assert!(HugePageInfo::parse("MemTotal: 1 kB\n", 0, 0).is_err());
+        assert!(HugePageInfo::parse("Hugepagesize: 2048 kB\n", 0, 0).is_err());
+        assert!(HugePageInfo::parse("HugePages_Free: x\nHugepagesize: 2048 kB\n", 0, 0).is_err());
+    }
+
+    #[test]
+    fn required_pages() {
+        let mut info = HugePageInfo::parse(MEMINFO, 0, 0).unwrap();
+        // 256 MiB cache
+        assert_eq!(info.required_pages(false).unwrap(), 128);
+        // 2080 MiB - 64 bytes dataset
+        assert_eq!(info.required_pages(true).unwrap(), 128 + 1040);
+        assert!(info.can_allocate(false).unwrap());
+        assert!(info.can_allocate(true).unwrap());
+
+        info.free_default_pages = 1167;
+        assert!(!info.can_allocate(true).unwrap());
+        info.free_default_pages = 127;
+        assert!(!info.can_allocate(false).unwrap());
+
+        info.default_page_size = 1024 * 1024 * 1024;
+        assert_eq!(info.required_pages(true).unwrap(), 1 + 3);
+        info.default_page_size = 0;
+        assert!(info.required_pages(false).is_err());
+    }
+
+    #[test]
+    fn probe() {
+        // Not every host has huge pages configured, but every Linux kernel with hugetlbfs reports them
+        if let Ok(info) = HugePageInfo::probe() {
+            assert!(info.default_page_size > 0);
+        }
+    }
+}
@@ -34,12 +34,59 @@
 //!
 //! [RandomX github repo]: <https://github.com/tevador/RandomX>
 //! [design document]: <https://github.com/tevador/RandomX/blob/master/doc/design.md>
+mod backend;
 mod bindings;
+#[cfg(feature = "ffi")]
+mod budget;
+mod builder;
+#[cfg(any(feature = "ffi", feature = "pure-rust"))]
+mod cache_export;
+#[cfg(any(feature = "ffi", feature = "pure-rust"))]
+mod checksum;
+#[cfg(feature = "ffi")]
+mod dataset_file;
+mod difficulty;
+#[cfg(feature = "ffi")]
+mod factory;
+#[cfg(feature = "test-fake")]
+mod fake;
+mod hash;
+#[cfg(all(target_os = "linux", feature = "ffi"))]
+mod hugepages;
+#[cfg(feature = "ffi")]
+mod miner;
+#[cfg(feature = "ffi")]
+mod pipeline;
+#[cfg(feature = "pure-rust")]
+mod pure;
+#[cfg(feature = "ffi")]
+mod rotation;
+#[cfg(all(target_os = "linux", feature = "ffi"))]
+mod shared;
 /// Test utilities for fuzzing
+#[cfg(feature = "ffi")]
 pub mod test_utils;
 
-use std::{convert::TryFrom, num::TryFromIntError, ptr, sync::Arc};
+use std::num::TryFromIntError;
+#[cfg(feature = "ffi")]
+use std::{
+    collections::hash_map::RandomState,
+    convert::TryFrom,
+    hash::{BuildHasher, Hasher},
+    ops::Range,
+    ptr,
+    slice,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread,
+};
 
+#[cfg(feature = "ffi")]
+pub use backend::FfiBackend;
+pub use backend::RandomXBackend;
+#[cfg(feature = "ffi")]
 use bindings::{
     randomx_alloc_cache,
     randomx_alloc_dataset,
@@ -57,12 +104,36 @@ use bindings::{
     randomx_vm,
     randomx_vm_set_cache,
     randomx_vm_set_dataset,
+    RANDOMX_ARGON_MEMORY,
     RANDOMX_HASH_SIZE,
 };
 use bitflags::bitflags;
+#[cfg(feature = "ffi")]
+pub use budget::ModeConfig;
+pub use builder::FlagConflict;
+#[cfg(feature = "ffi")]
+pub use builder::RandomXVMBuilder;
+pub use difficulty::difficulty_to_target;
+#[cfg(feature = "ffi")]
+pub use factory::{PooledVM, RandomXFactory};
+#[cfg(feature = "test-fake")]
+pub use fake::{FakeBackend, FakeRandomXCache, FakeRandomXDataset, FakeRandomXVM};
+pub use hash::RandomXHash;
+#[cfg(all(target_os = "linux", feature = "ffi"))]
+pub use hugepages::HugePageInfo;
+#[cfg(feature = "ffi")]
 use libc::{c_ulong, c_void};
+#[cfg(feature = "ffi")]
+pub use miner::{FoundNonce, NonceSearch, SearchOutcome};
+#[cfg(feature = "ffi")]
+pub use pipeline::HashPipeline;
+#[cfg(feature = "pure-rust")]
+pub use pure::{PureBackend, PureRandomXCache, PureRandomXVM};
+#[cfg(feature = "ffi")]
+pub use rotation::KeyRotationManager;
 use thiserror::Error;
 
+#[cfg(feature = "ffi")]
 use crate::bindings::{
     randomx_calculate_hash_first,
     randomx_calculate_hash_last,
@@ -95,6 +166,7 @@ bitflags! {
     }
 }
 
+#[cfg(feature = "ffi")]
 impl RandomXFlag {
     /// Returns the recommended flags to be used.
     ///
@@ -109,6 +181,26 @@ impl RandomXFlag {
             bits: unsafe { randomx_get_flags() },
         }
     }
+
+    /// Returns the recommended flags like [`RandomXFlag::get_recommended_flags`], and includes FLAG_LARGE_PAGES if
+    /// there are enough free huge pages for the cache and, if `full_mem` is set, the dataset.
+    ///
+    /// Huge pages are only probed on Linux, see [`HugePageInfo`]. On other platforms FLAG_LARGE_PAGES is never
+    /// included.
+    #[cfg_attr(not(target_os = "linux"), allow(unused_variables))]
+    pub fn get_recommended_flags_with_large_pages(full_mem: bool) -> RandomXFlag {
+        let flags = RandomXFlag::get_recommended_flags();
+        #[cfg(target_os = "linux")]
+        {
+            let large_pages = HugePageInfo::probe()
+                .and_then(|info| info.can_allocate(full_mem))
+                .unwrap_or(false);
+            if large_pages {
+                return flags | RandomXFlag::FLAG_LARGE_PAGES;
+            }
+        }
+        flags
+    }
 }
 
 impl Default for RandomXFlag {
@@ -125,35 +217,94 @@ pub enum RandomXError {
     CreationError(String),
     #[error("Problem with configuration flags: {0}")]
     FlagConfigError(String),
+    #[error("Invalid flag combination: {0}")]
+    InvalidFlags(#[from] FlagConflict),
     #[error("Problem with parameters supplied: {0}")]
     ParameterError(String),
     #[error("Failed to convert Int to usize")]
     TryFromIntError(#[from] TryFromIntError),
+    #[error("Operation was cancelled")]
+    Cancelled,
+    #[error("Not enough memory: {required} bytes required, {available} bytes available")]
+    InsufficientMemory { required: u64, available: u64 },
+    #[error("Pool limit reached: {0}")]
+    PoolExhausted(String),
+    #[error("Dataset file does not match: {0}")]
+    DatasetFileMismatch(String),
+    #[error("Dataset file is corrupt: {0}")]
+    DatasetFileCorrupt(String),
+    #[error("Exported cache does not match: {0}")]
+    CacheExportMismatch(String),
+    #[error("Exported cache is corrupt: {0}")]
+    CacheExportCorrupt(String),
+    #[error("Shared dataset is stale: {0}")]
+    SharedDatasetStale(String),
+    #[error("I/O error: {0}")]
+    IoError(String),
     #[error("Unknown problem running RandomX: {0}")]
     Other(String),
 }
 
+#[cfg(feature = "ffi")]
 #[derive(Debug)]
 struct RandomXCacheInner {
     cache_ptr: *mut randomx_cache,
+    large_pages: bool,
+    key: Vec<u8>,
 }
 
+// SAFETY: The cache memory is only written by `randomx_init_cache` while the cache is being constructed. Afterwards
+// it is only read, by VMs and during dataset initialization, so it can be shared and released from any thread.
+#[cfg(feature = "ffi")]
+unsafe impl Send for RandomXCacheInner {}
+#[cfg(feature = "ffi")]
+unsafe impl Sync for RandomXCacheInner {}
+
+#[cfg(feature = "ffi")]
 impl Drop for RandomXCacheInner {
     /// De-allocates memory for the `cache` object
     fn drop(&mut self) {
+        // RandomX asserts that the cache it releases is not null
+        if self.cache_ptr.is_null() {
+            return;
+        }
         unsafe {
             randomx_release_cache(self.cache_ptr);
         }
     }
 }
 
+#[cfg(feature = "ffi")]
+/// The start of `randomx_cache` in RandomX's `dataset.hpp`, which begins with a pointer to the cache memory.
+#[repr(C)]
+struct CacheHandle {
+    memory: *const u8,
+}
+
+#[cfg(feature = "ffi")]
 #[derive(Debug, Clone)]
 /// The Cache is used for light verification and Dataset construction.
+///
+/// Caches compare equal if they were initialized with the same key.
 pub struct RandomXCache {
     inner: Arc<RandomXCacheInner>,
 }
 
+#[cfg(feature = "ffi")]
+impl PartialEq for RandomXCache {
+    fn eq(&self, other: &Self) -> bool {
+        self.inner.key == other.inner.key
+    }
+}
+
+#[cfg(feature = "ffi")]
+impl Eq for RandomXCache {}
+
+#[cfg(feature = "ffi")]
 impl RandomXCache {
+    /// The size of the cache memory in bytes.
+    pub const MEMORY_SIZE: usize = RANDOMX_ARGON_MEMORY as usize * 1024;
+
     /// Creates and alllcates memory for a new cache object, and initializes it with
     /// the key value.
     ///
@@ -174,7 +325,11 @@ impl RandomXCache {
             if cache_ptr.is_null() {
                 Err(RandomXError::CreationError("Could not allocate cache".to_string()))
             } else {
-                let inner = RandomXCacheInner { cache_ptr };
+                let inner = RandomXCacheInner {
+                    cache_ptr,
+                    large_pages: flags.contains(RandomXFlag::FLAG_LARGE_PAGES),
+                    key: key.to_vec(),
+                };
                 let result = RandomXCache { inner: Arc::new(inner) };
                 let key_ptr = key.as_ptr() as *mut c_void;
                 let key_size = key.len();
@@ -185,32 +340,153 @@ impl RandomXCache {
             }
         }
     }
+
+    /// Creates a new cache object like [`RandomXCache::new`], allocating its memory in large pages if possible and
+    /// in normal pages otherwise.
+    ///
+    /// Use [`RandomXCache::has_large_pages`] to find out which allocation succeeded.
+    pub fn new_with_fallback(flags: RandomXFlag, key: &[u8]) -> Result<RandomXCache, RandomXError> {
+        match RandomXCache::new(flags | RandomXFlag::FLAG_LARGE_PAGES, key) {
+            Err(RandomXError::CreationError(_)) => RandomXCache::new(flags - RandomXFlag::FLAG_LARGE_PAGES, key),
+            result => result,
+        }
+    }
+
+    /// Returns true if the cache memory was allocated in large pages.
+    pub fn has_large_pages(&self) -> bool {
+        self.inner.large_pages
+    }
+
+    /// Returns the key the cache was initialized with.
+    pub fn key(&self) -> &[u8] {
+        &self.inner.key
+    }
+
+    /// Returns the Argon2d-filled cache memory without copying it, or an error on failure.
+    pub fn memory(&self) -> Result<&[u8], RandomXError> {
+        // SAFETY: `cache_ptr` points to an initialized `randomx_cache`, which starts with the memory pointer
+        let memory = unsafe { (*self.inner.cache_ptr.cast::<CacheHandle>()).memory };
+        if memory.is_null() {
+            return Err(RandomXError::Other("Could not get cache memory".into()));
+        }
+        // SAFETY: The cache memory is `MEMORY_SIZE` bytes long and only written while the cache is being
+        // constructed. It lives as long as `self.inner`, which the returned slice borrows.
+        Ok(unsafe { slice::from_raw_parts(memory, RandomXCache::MEMORY_SIZE) })
+    }
+
+    /// Exports the key and memory of the cache, with a checksum.
+    ///
+    /// The RandomX C library can only fill a cache by running Argon2d, so the export cannot be imported back into a
+    /// `RandomXCache`. Light-mode verifiers built with the `pure-rust` feature can import it with
+    /// `PureRandomXCache::import` to skip Argon2d on restart.
+    pub fn export(&self) -> Result<Vec<u8>, RandomXError> {
+        cache_export::encode(self.key(), self.memory()?)
+    }
+
+    /// Computes dataset item `index` from the cache, the way a light-mode `VM` does, without a dataset.
+    pub fn compute_dataset_item(&self, index: u32) -> Result<[u8; RandomXDataset::ITEM_SIZE], RandomXError> {
+        let count = RandomXDataset::count()?;
+        if index >= count {
+            return Err(RandomXError::ParameterError(format!(
+                "item index {index} is out of range, item count: {count}"
+            )));
+        }
+        let mut item = [0u8; RandomXDataset::ITEM_SIZE];
+        self.init_dataset_item(index, &mut item)?;
+        Ok(item)
+    }
+
+    /// Writes dataset item `index` to `item`.
+    ///
+    /// `randomx_init_dataset` only takes a whole dataset and writes item `index` to `memory + index * ITEM_SIZE`, so
+    /// the handle passed to it points `index` items before `item`. The handle's pointer is created with
+    /// `wrapping_sub` and never dereferenced by Rust; RandomX adds the offset back before writing, and only writes the
+    /// `ITEM_SIZE` bytes of `item`, which `lib_compute_dataset_item_stays_in_bounds` checks.
+    // Conversions may be lossy on Windows or Linux
+    #[allow(clippy::useless_conversion)]
+    fn init_dataset_item(&self, index: u32, item: &mut [u8; RandomXDataset::ITEM_SIZE]) -> Result<(), RandomXError> {
+        let mut handle = DatasetHandle {
+            memory: item
+                .as_mut_ptr()
+                .wrapping_sub(usize::try_from(index)? * RandomXDataset::ITEM_SIZE),
+            dealloc: ptr::null(),
+        };
+        unsafe {
+            randomx_init_dataset(
+                (&mut handle as *mut DatasetHandle).cast::<randomx_dataset>(),
+                self.inner.cache_ptr,
+                c_ulong::from(index),
+                1,
+            );
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "ffi")]
+/// The layout of `randomx_dataset` in RandomX's `dataset.hpp`.
+///
+/// RandomX has no API to wrap memory it did not allocate, so shared datasets and single item computations hand
+/// RandomX a pointer to this struct instead. RandomX only reads `memory` from it, in `randomx_init_dataset` and
+/// `randomx_vm_set_dataset`.
+#[repr(C)]
+#[derive(Debug)]
+struct DatasetHandle {
+    memory: *mut u8,
+    dealloc: *const c_void,
 }
 
+#[cfg(feature = "ffi")]
 #[derive(Debug)]
 struct RandomXDatasetInner {
     dataset_ptr: *mut randomx_dataset,
     dataset_count: u32,
-    #[allow(dead_code)]
-    cache: RandomXCache,
+    large_pages: bool,
+    // The cache the dataset was initialized with, or `None` if it was mapped from another process
+    cache: Option<RandomXCache>,
+    #[cfg(target_os = "linux")]
+    shared: Option<shared::SharedMapping>,
 }
 
+// SAFETY: The dataset memory is only written by `randomx_init_dataset`, either while the dataset is being
+// constructed or by the workers of `new_parallel` and `new_shared`, which each write a disjoint range of items.
+// Afterwards it is only read by VMs, so it can be shared and released from any thread.
+#[cfg(feature = "ffi")]
+unsafe impl Send for RandomXDatasetInner {}
+#[cfg(feature = "ffi")]
+unsafe impl Sync for RandomXDatasetInner {}
+
+#[cfg(feature = "ffi")]
 impl Drop for RandomXDatasetInner {
     /// De-allocates memory for the `dataset` object.
     fn drop(&mut self) {
+        // A shared dataset is not owned by RandomX, its mapping is released when `shared` is dropped
+        #[cfg(target_os = "linux")]
+        if self.shared.is_some() {
+            return;
+        }
+        // RandomX asserts that the dataset it releases is not null
+        if self.dataset_ptr.is_null() {
+            return;
+        }
         unsafe {
             randomx_release_dataset(self.dataset_ptr);
         }
     }
 }
 
+#[cfg(feature = "ffi")]
 #[derive(Debug, Clone)]
 /// The Dataset is a read-only memory structure that is used during VM program execution.
 pub struct RandomXDataset {
     inner: Arc<RandomXDatasetInner>,
 }
 
+#[cfg(feature = "ffi")]
 impl RandomXDataset {
+    /// The size of a dataset item in bytes.
+    pub const ITEM_SIZE: usize = 64;
+
     /// Creates a new dataset object, allocates memory to the `dataset` object and initializes it.
     ///
     /// `flags` is one of the following:
@@ -220,41 +496,232 @@ impl RandomXDataset {
     /// `cache` is a cache object.
     ///
     /// `start` is the item number where initialization should start, recommended to pass in 0.
-    // Conversions may be lossy on Windows or Linux
-    #[allow(clippy::useless_conversion)]
     pub fn new(flags: RandomXFlag, cache: RandomXCache, start: u32) -> Result<RandomXDataset, RandomXError> {
         let item_count = RandomXDataset::count()
             .map_err(|e| RandomXError::CreationError(format!("Could not get dataset count: {e:?}")))?;
+        if start >= item_count {
+            return Err(RandomXError::CreationError(format!(
+                "start must be less than item_count: start: {start}, item_count: {item_count}",
+            )));
+        }
+
+        let result = RandomXDataset::alloc(flags, cache, item_count)?;
+        result.init_items(start, item_count - start);
+        Ok(result)
+    }
 
-        let test = unsafe { randomx_alloc_dataset(flags.bits) };
-        if test.is_null() {
+    /// Creates a new dataset object, allocates memory to the `dataset` object and initializes it in chunks,
+    /// reporting progress and checking for cancellation between chunks.
+    ///
+    /// `flags` is one of the following:
+    /// * FLAG_DEFAULT
+    /// * FLAG_LARGE_PAGES
+    ///
+    /// `cache` is a cache object.
+    ///
+    /// `chunk_size` is the number of items initialized between progress reports.
+    ///
+    /// `cancel` is checked before every chunk. Once it is set, initialization stops and
+    /// `RandomXError::Cancelled` is returned.
+    ///
+    /// `progress` is called after every chunk with the number of items initialized so far and the total number of
+    /// items.
+    pub fn new_with_progress<F>(
+        flags: RandomXFlag,
+        cache: RandomXCache,
+        chunk_size: u32,
+        cancel: &AtomicBool,
+        mut progress: F,
+    ) -> Result<RandomXDataset, RandomXError>
+    where
+        F: FnMut(u32, u32),
+    {
+        if chunk_size == 0 {
+            return Err(RandomXError::ParameterError(
+                "chunk_size must be greater than 0".to_string(),
+            ));
+        }
+        let item_count = RandomXDataset::count()
+            .map_err(|e| RandomXError::CreationError(format!("Could not get dataset count: {e:?}")))?;
+
+        let result = RandomXDataset::alloc(flags, cache, item_count)?;
+        let mut done = 0;
+        while done < item_count {
+            if cancel.load(Ordering::Relaxed) {
+                return Err(RandomXError::Cancelled);
+            }
+            let count = chunk_size.min(item_count - done);
+            result.init_items(done, count);
+            done += count;
+            progress(done, item_count);
+        }
+        Ok(result)
+    }
+
+    /// Creates a new dataset object and allocates memory to it, without initializing any items. The memory is zeroed,
+    /// so it can be read safely before the items are filled.
+    ///
+    /// The items are filled with [`RandomXDataset::init_range`], or with [`RandomXDataset::write_items`] from items
+    /// computed elsewhere, so dataset generation can be split across processes or hosts. All items must be filled
+    /// before the dataset is attached to a `VM`, a `VM` hashing with zeroed items returns wrong hashes.
+    pub fn new_uninit(flags: RandomXFlag, cache: RandomXCache) -> Result<RandomXDataset, RandomXError> {
+        let item_count = RandomXDataset::count()
+            .map_err(|e| RandomXError::CreationError(format!("Could not get dataset count: {e:?}")))?;
+        let result = RandomXDataset::alloc(flags, cache, item_count)?;
+        let memory_size = RandomXDataset::memory_size()?;
+        // SAFETY: RandomX allocated `memory_size` bytes for the dataset, and nothing else can access it yet
+        unsafe {
+            ptr::write_bytes(
+                randomx_get_dataset_memory(result.inner.dataset_ptr).cast::<u8>(),
+                0,
+                memory_size,
+            );
+        }
+        Ok(result)
+    }
+
+    /// Creates a new dataset object, allocates memory to the `dataset` object and initializes it using
+    /// multiple threads.
+    ///
+    /// `flags` is one of the following:
+    /// * FLAG_DEFAULT
+    /// * FLAG_LARGE_PAGES
+    ///
+    /// `cache` is a cache object.
+    ///
+    /// `threads` is the number of worker threads the dataset items are split across. Each worker initializes its
+    /// own contiguous range of items, and this function returns once all workers have finished.
+    pub fn new_parallel(flags: RandomXFlag, cache: RandomXCache, threads: u32) -> Result<RandomXDataset, RandomXError> {
+        RandomXDataset::new_parallel_with_cancel(flags, cache, threads, &AtomicBool::new(false))
+    }
+
+    /// Creates a new dataset object like [`RandomXDataset::new_parallel`], but stops initializing once `cancel` is
+    /// set and returns `RandomXError::Cancelled`. Every worker checks `cancel` between chunks of items.
+    pub fn new_parallel_with_cancel(
+        flags: RandomXFlag,
+        cache: RandomXCache,
+        threads: u32,
+        cancel: &AtomicBool,
+    ) -> Result<RandomXDataset, RandomXError> {
+        if threads == 0 {
+            return Err(RandomXError::ParameterError(
+                "threads must be greater than 0".to_string(),
+            ));
+        }
+        let item_count = RandomXDataset::count()
+            .map_err(|e| RandomXError::CreationError(format!("Could not get dataset count: {e:?}")))?;
+
+        let result = RandomXDataset::alloc(flags, cache, item_count)?;
+        result.init_items_parallel(threads, cancel)?;
+        Ok(result)
+    }
+
+    /// Returns the key the dataset was built for.
+    fn key(&self) -> &[u8] {
+        #[cfg(target_os = "linux")]
+        if let Some(shared) = &self.inner.shared {
+            return shared.key();
+        }
+        self.inner.cache.as_ref().map_or(&[], |cache| &cache.inner.key)
+    }
+
+    /// Initializes all items of the `dataset`, spreading the work over `threads` threads.
+    fn init_items_parallel(&self, threads: u32, cancel: &AtomicBool) -> Result<(), RandomXError> {
+        // Number of items a worker initializes between checks of `cancel`
+        const CHUNK_SIZE: u32 = 1 << 16;
+        let item_count = self.inner.dataset_count;
+        let threads = threads.min(item_count);
+        let per_thread = item_count / threads;
+        let remainder = item_count % threads;
+        thread::scope(|scope| {
+            let mut start = 0;
+            for i in 0..threads {
+                // Spread the remainder over the first few workers
+                let end = start + per_thread + u32::from(i < remainder);
+                scope.spawn(move || {
+                    let mut done = start;
+                    while done < end && !cancel.load(Ordering::Relaxed) {
+                        let count = CHUNK_SIZE.min(end - done);
+                        self.init_items(done, count);
+                        done += count;
+                    }
+                });
+                start = end;
+            }
+        });
+        if cancel.load(Ordering::Relaxed) {
+            Err(RandomXError::Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Initializes `count` items of the `dataset`, beginning at item `start`.
+    // Conversions may be lossy on Windows or Linux
+    #[allow(clippy::useless_conversion)]
+    fn init_items(&self, start: u32, count: u32) {
+        unsafe {
+            randomx_init_dataset(
+                self.inner.dataset_ptr,
+                self.inner
+                    .cache
+                    .as_ref()
+                    .expect("datasets are only initialized by their own constructors, which have a cache")
+                    .inner
+                    .cache_ptr,
+                c_ulong::from(start),
+                c_ulong::from(count),
+            );
+        }
+    }
+
+    /// Creates a new dataset object like [`RandomXDataset::new_parallel`], allocating its memory in large pages if
+    /// possible and in normal pages otherwise.
+    ///
+    /// Use [`RandomXDataset::has_large_pages`] to find out which allocation succeeded.
+    pub fn new_with_fallback(
+        flags: RandomXFlag,
+        cache: RandomXCache,
+        threads: u32,
+    ) -> Result<RandomXDataset, RandomXError> {
+        match RandomXDataset::new_parallel(flags | RandomXFlag::FLAG_LARGE_PAGES, cache.clone(), threads) {
+            Err(RandomXError::CreationError(_)) => {
+                RandomXDataset::new_parallel(flags - RandomXFlag::FLAG_LARGE_PAGES, cache, threads)
+            },
+            result => result,
+        }
+    }
+
+    /// Returns true if the dataset memory was allocated in large pages.
+    pub fn has_large_pages(&self) -> bool {
+        self.inner.large_pages
+    }
+
+    /// Allocates memory for a new, uninitialized `dataset` object.
+    fn alloc(flags: RandomXFlag, cache: RandomXCache, item_count: u32) -> Result<RandomXDataset, RandomXError> {
+        let dataset_ptr = unsafe { randomx_alloc_dataset(flags.bits) };
+        if dataset_ptr.is_null() {
             Err(RandomXError::CreationError("Could not allocate dataset".to_string()))
         } else {
             let inner = RandomXDatasetInner {
-                dataset_ptr: test,
+                dataset_ptr,
                 dataset_count: item_count,
-                cache,
+                large_pages: flags.contains(RandomXFlag::FLAG_LARGE_PAGES),
+                cache: Some(cache),
+                #[cfg(target_os = "linux")]
+                shared: None,
             };
-            let result = RandomXDataset { inner: Arc::new(inner) };
-
-            if start < item_count {
-                unsafe {
-                    randomx_init_dataset(
-                        result.inner.dataset_ptr,
-                        result.inner.cache.inner.cache_ptr,
-                        c_ulong::from(start),
-                        c_ulong::from(item_count),
-                    );
-                }
-                Ok(result)
-            } else {
-                Err(RandomXError::CreationError(format!(
-                    "start must be less than item_count: start: {start}, item_count: {item_count}",
-                )))
-            }
+            Ok(RandomXDataset { inner: Arc::new(inner) })
         }
     }
 
+    /// Returns the size of the dataset memory in bytes or an error on failure.
+    pub fn memory_size() -> Result<usize, RandomXError> {
+        usize::try_from(RandomXDataset::count()?)?
+            .checked_mul(RandomXDataset::ITEM_SIZE)
+            .ok_or_else(|| RandomXError::Other("Dataset size overflows usize".to_string()))
+    }
+
     /// Returns the number of items in the `dataset` or an error on failure.
     pub fn count() -> Result<u32, RandomXError> {
         match unsafe { randomx_dataset_item_count() } {
@@ -269,23 +736,150 @@ impl RandomXDataset {
         }
     }
 
-    /// Returns the values of the internal memory buffer of the `dataset` or an error on failure.
+    /// Returns a copy of the internal memory buffer of the `dataset` or an error on failure.
+    #[deprecated(note = "copies the whole dataset, use `memory` to borrow it instead")]
     pub fn get_data(&self) -> Result<Vec<u8>, RandomXError> {
+        Ok(self.memory()?.to_vec())
+    }
+
+    /// Returns the internal memory buffer of the `dataset` without copying it, or an error on failure.
+    ///
+    /// The buffer holds `RandomXDataset::count()` items of `RandomXDataset::ITEM_SIZE` bytes each.
+    pub fn memory(&self) -> Result<&[u8], RandomXError> {
         let memory = unsafe { randomx_get_dataset_memory(self.inner.dataset_ptr) };
         if memory.is_null() {
             Err(RandomXError::Other("Could not get dataset memory".into()))
         } else {
-            let count = usize::try_from(self.inner.dataset_count)?;
-            let mut result: Vec<u8> = vec![0u8; count];
-            let n = usize::try_from(self.inner.dataset_count)?;
-            unsafe {
-                libc::memcpy(result.as_mut_ptr() as *mut c_void, memory, n);
+            let len = usize::try_from(self.inner.dataset_count)?
+                .checked_mul(RandomXDataset::ITEM_SIZE)
+                .ok_or_else(|| RandomXError::Other("Dataset size overflows usize".to_string()))?;
+            // SAFETY: The dataset memory holds `dataset_count` items and is only written while the dataset is being
+            // constructed, or through `&mut self`. It lives as long as `self.inner`, which the returned slice borrows.
+            Ok(unsafe { slice::from_raw_parts(memory as *const u8, len) })
+        }
+    }
+
+    /// Returns the dataset item at `index` without copying it, or an error on failure.
+    pub fn item(&self, index: u32) -> Result<&[u8; RandomXDataset::ITEM_SIZE], RandomXError> {
+        if index >= self.inner.dataset_count {
+            return Err(RandomXError::ParameterError(format!(
+                "item index {index} is out of range, item count: {}",
+                self.inner.dataset_count
+            )));
+        }
+        let start = usize::try_from(index)? * RandomXDataset::ITEM_SIZE;
+        let item = &self.memory()?[start..start + RandomXDataset::ITEM_SIZE];
+        Ok(<&[u8; RandomXDataset::ITEM_SIZE]>::try_from(item).expect("item slice has the item size"))
+    }
+
+    /// Compares `samples` randomly chosen items of the `dataset` with items computed from its cache, see
+    /// [`RandomXCache::compute_dataset_item`]. Returns false if any of them differ, which means the dataset is
+    /// corrupt or was built for another key.
+    ///
+    /// Datasets opened from shared memory have no cache, use [`RandomXDataset::verify_sample_with`] for those.
+    pub fn verify_sample(&self, samples: u32) -> Result<bool, RandomXError> {
+        let cache = self
+            .inner
+            .cache
+            .as_ref()
+            .ok_or_else(|| RandomXError::ParameterError("dataset has no cache".to_string()))?;
+        self.verify_sample_with(cache, samples)
+    }
+
+    /// Compares `samples` randomly chosen items of the `dataset` with items computed from `cache`, like
+    /// [`RandomXDataset::verify_sample`].
+    pub fn verify_sample_with(&self, cache: &RandomXCache, samples: u32) -> Result<bool, RandomXError> {
+        // Seeded per call from the randomly keyed std hasher, so repeated checks sample different items
+        let mut state = RandomState::new().build_hasher().finish();
+        for _ in 0..samples {
+            // SplitMix64
+            state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+            let mut z = state;
+            z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+            z ^= z >> 31;
+            let index = u32::try_from(z % u64::from(self.inner.dataset_count))?;
+            if *self.item(index)? != cache.compute_dataset_item(index)? {
+                return Ok(false);
             }
-            Ok(result)
+        }
+        Ok(true)
+    }
+
+    /// Returns `count` items of the `dataset`, beginning at item `start`, without copying them.
+    pub fn items(&self, start: u32, count: u32) -> Result<&[u8], RandomXError> {
+        let range = self.item_range(start, count)?;
+        Ok(&self.memory()?[range])
+    }
+
+    /// Initializes `count` items of the `dataset` from its cache, beginning at item `start`.
+    ///
+    /// The `dataset` must not be in use: this fails if it has been cloned, attached to a `VM` or shared with other
+    /// processes.
+    pub fn init_range(&mut self, start: u32, count: u32) -> Result<(), RandomXError> {
+        self.item_range(start, count)?;
+        self.check_exclusive()?;
+        self.init_items(start, count);
+        Ok(())
+    }
+
+    /// Writes `items`, a whole number of items computed elsewhere, e.g. with [`RandomXDataset::init_range`] on
+    /// another host, into the `dataset`, beginning at item `start`.
+    ///
+    /// The `dataset` must not be in use, as for [`RandomXDataset::init_range`].
+    #[allow(unknown_lints, clippy::manual_is_multiple_of)] // is_multiple_of needs Rust 1.87
+    pub fn write_items(&mut self, start: u32, items: &[u8]) -> Result<(), RandomXError> {
+        if items.len() % RandomXDataset::ITEM_SIZE != 0 {
+            return Err(RandomXError::ParameterError(format!(
+                "items must be a multiple of {} bytes long",
+                RandomXDataset::ITEM_SIZE
+            )));
+        }
+        let range = self.item_range(start, u32::try_from(items.len() / RandomXDataset::ITEM_SIZE)?)?;
+        self.check_exclusive()?;
+        let memory = unsafe { randomx_get_dataset_memory(self.inner.dataset_ptr) };
+        if memory.is_null() {
+            return Err(RandomXError::Other("Could not get dataset memory".into()));
+        }
+        // SAFETY: `range` lies within the dataset memory, and nothing else refers to the dataset
+        unsafe {
+            ptr::copy_nonoverlapping(items.as_ptr(), memory.cast::<u8>().add(range.start), items.len());
+        }
+        Ok(())
+    }
+
+    /// Returns the byte range of `count` items beginning at item `start`, or an error if it is out of range.
+    fn item_range(&self, start: u32, count: u32) -> Result<Range<usize>, RandomXError> {
+        match start.checked_add(count) {
+            Some(end) if end <= self.inner.dataset_count => {
+                Ok(usize::try_from(start)? * RandomXDataset::ITEM_SIZE..
+                    usize::try_from(end)? * RandomXDataset::ITEM_SIZE)
+            },
+            _ => Err(RandomXError::ParameterError(format!(
+                "items {start}..{start}+{count} are out of range, item count: {}",
+                self.inner.dataset_count
+            ))),
         }
     }
+
+    /// Returns an error unless nothing else refers to the `dataset`, so it can be written.
+    fn check_exclusive(&mut self) -> Result<(), RandomXError> {
+        let inner = Arc::get_mut(&mut self.inner)
+            .ok_or_else(|| RandomXError::ParameterError("dataset is cloned or attached to a VM".to_string()))?;
+        #[cfg(target_os = "linux")]
+        if inner.shared.is_some() {
+            return Err(RandomXError::ParameterError(
+                "shared datasets are read-only".to_string(),
+            ));
+        }
+        if inner.cache.is_none() {
+            return Err(RandomXError::ParameterError("dataset has no cache".to_string()));
+        }
+        Ok(())
+    }
 }
 
+#[cfg(feature = "ffi")]
 #[derive(Debug)]
 /// The RandomX Virtual Machine (VM) is a complex instruction set computer that executes generated programs.
 pub struct RandomXVM {
@@ -295,6 +889,13 @@ pub struct RandomXVM {
     linked_dataset: Option<RandomXDataset>,
 }
 
+// SAFETY: A VM has no affinity to the thread that created it, so it can be moved to another thread. It is not `Sync`
+// because hashing mutates the VM's scratchpad and registers, so every thread needs its own VM. The cache and dataset
+// it links to are `Send` and `Sync`.
+#[cfg(feature = "ffi")]
+unsafe impl Send for RandomXVM {}
+
+#[cfg(feature = "ffi")]
 impl Drop for RandomXVM {
     /// De-allocates memory for the `VM` object.
     fn drop(&mut self) {
@@ -304,6 +905,7 @@ impl Drop for RandomXVM {
     }
 }
 
+#[cfg(feature = "ffi")]
 impl RandomXVM {
     /// Creates a new `VM` and initializes it, error on failure.
     ///
@@ -345,6 +947,11 @@ impl RandomXVM {
                     .map(|data| data.inner.dataset_ptr)
                     .unwrap_or_else(ptr::null_mut);
                 let vm = unsafe { randomx_create_vm(flags.bits, cache_ptr, dataset_ptr) };
+                if vm.is_null() {
+                    return Err(RandomXError::CreationError(format!(
+                        "Could not create VM with flags {flags:?}"
+                    )));
+                }
                 Ok(RandomXVM {
                     vm,
                     flags,
@@ -355,6 +962,45 @@ impl RandomXVM {
         }
     }
 
+    /// Creates a new `VM` like [`RandomXVM::new`], but if RandomX cannot create it with `flags`, retries with
+    /// progressively fewer flags:
+    /// 1. without FLAG_LARGE_PAGES
+    /// 2. without FLAG_LARGE_PAGES, FLAG_JIT and FLAG_SECURE
+    ///
+    /// The flags that were actually used are returned by [`RandomXVM::flags`].
+    #[allow(clippy::needless_pass_by_value)] // Takes the same arguments as `RandomXVM::new`
+    pub fn new_with_fallback(
+        flags: RandomXFlag,
+        cache: Option<RandomXCache>,
+        dataset: Option<RandomXDataset>,
+    ) -> Result<RandomXVM, RandomXError> {
+        let without_large_pages = flags - RandomXFlag::FLAG_LARGE_PAGES;
+        let without_jit = without_large_pages - RandomXFlag::FLAG_JIT - RandomXFlag::FLAG_SECURE;
+        let mut result = RandomXVM::new(flags, cache.clone(), dataset.clone());
+        let mut tried = flags;
+        for fallback in [without_large_pages, without_jit] {
+            if !matches!(result, Err(RandomXError::CreationError(_))) {
+                break;
+            }
+            if fallback != tried {
+                result = RandomXVM::new(fallback, cache.clone(), dataset.clone());
+                tried = fallback;
+            }
+        }
+        result
+    }
+
+    /// Opens a [`HashPipeline`] that hashes a stream of inputs on this `VM`, starting with `input`, error if `input`
+    /// is empty.
+    pub fn hash_pipeline(&mut self, input: &[u8]) -> Result<HashPipeline<'_>, RandomXError> {
+        HashPipeline::new(self, input)
+    }
+
+    /// Returns the flags the `VM` was created with.
+    pub fn flags(&self) -> RandomXFlag {
+        self.flags
+    }
+
     /// Re-initializes the `VM` with a new cache that was initialised without
     /// RandomXFlag::FLAG_FULL_MEM.
     pub fn reinit_cache(&mut self, cache: RandomXCache) -> Result<(), RandomXError> {
@@ -362,6 +1008,8 @@ impl RandomXVM {
             Err(RandomXError::FlagConfigError(
                 "Cannot reinit cache with FLAG_FULL_MEM set".to_string(),
             ))
+        } else if cache.inner.cache_ptr.is_null() {
+            Err(RandomXError::ParameterError("cache is null".to_string()))
         } else {
             unsafe {
                 randomx_vm_set_cache(self.vm, cache.inner.cache_ptr);
@@ -374,7 +1022,9 @@ impl RandomXVM {
     /// Re-initializes the `VM` with a new dataset that was initialised with
     /// RandomXFlag::FLAG_FULL_MEM.
     pub fn reinit_dataset(&mut self, dataset: RandomXDataset) -> Result<(), RandomXError> {
-        if self.flags.contains(RandomXFlag::FLAG_FULL_MEM) {
+        if dataset.inner.dataset_ptr.is_null() {
+            Err(RandomXError::ParameterError("dataset is null".to_string()))
+        } else if self.flags.contains(RandomXFlag::FLAG_FULL_MEM) {
             unsafe {
                 randomx_vm_set_dataset(self.vm, dataset.inner.dataset_ptr);
             }
@@ -389,134 +1039,232 @@ impl RandomXVM {
 
     /// Calculates a RandomX hash value and returns it, error on failure.
     ///
-    /// `input` is a sequence of u8 to be hashed.
-    pub fn calculate_hash(&self, input: &[u8]) -> Result<Vec<u8>, RandomXError> {
+    /// `input` is a sequence of u8 to be hashed. The only error is an empty `input`: the `VM` was checked to have a
+    /// cache or dataset matching its flags when it was created, so the output of RandomX is returned as-is.
+    pub fn calculate_hash(&self, input: &[u8]) -> Result<RandomXHash, RandomXError> {
+        let mut output = [0u8; RANDOMX_HASH_SIZE as usize];
+        self.calculate_hash_into(input, &mut output)?;
+        Ok(RandomXHash::from(output))
+    }
+
+    /// Calculates a RandomX hash value like [`RandomXVM::calculate_hash`], writing it into `output` instead of
+    /// returning it.
+    pub fn calculate_hash_into(
+        &self,
+        input: &[u8],
+        output: &mut [u8; RANDOMX_HASH_SIZE as usize],
+    ) -> Result<(), RandomXError> {
         if input.is_empty() {
-            Err(RandomXError::ParameterError("input was empty".to_string()))
-        } else {
-            let size_input = input.len();
-            let input_ptr = input.as_ptr() as *mut c_void;
-            let arr = [0; RANDOMX_HASH_SIZE as usize];
-            let output_ptr = arr.as_ptr() as *mut c_void;
-            unsafe {
-                randomx_calculate_hash(self.vm, input_ptr, size_input, output_ptr);
-            }
-            // if this failed, arr should still be empty
-            if arr == [0; RANDOMX_HASH_SIZE as usize] {
-                Err(RandomXError::Other("RandomX calculated hash was empty".to_string()))
-            } else {
-                let result = arr.to_vec();
-                Ok(result)
-            }
+            return Err(RandomXError::ParameterError("input was empty".to_string()));
+        }
+        unsafe {
+            randomx_calculate_hash(
+                self.vm,
+                input.as_ptr().cast::<c_void>(),
+                input.len(),
+                output.as_mut_ptr().cast::<c_void>(),
+            );
         }
+        Ok(())
     }
 
     /// Calculates hashes from a set of inputs.
     ///
-    /// `input` is an array of a sequence of u8 to be hashed.
-    #[allow(clippy::needless_range_loop)] // Range loop is not only for indexing `input`
-    pub fn calculate_hash_set(&self, input: &[&[u8]]) -> Result<Vec<Vec<u8>>, RandomXError> {
-        if input.is_empty() {
-            // Empty set
+    /// `input` is an array of a sequence of u8 to be hashed. All inputs are checked before hashing starts, so an
+    /// error means that no hashes were calculated.
+    pub fn calculate_hash_set(&self, input: &[&[u8]]) -> Result<Vec<RandomXHash>, RandomXError> {
+        let mut output = vec![[0u8; RANDOMX_HASH_SIZE as usize]; input.len()];
+        self.calculate_hash_set_into(input, &mut output)?;
+        Ok(output.into_iter().map(RandomXHash::from).collect())
+    }
+
+    /// Calculates hashes from a set of inputs like [`RandomXVM::calculate_hash_set`], writing the hash of `input[i]`
+    /// into `output[i]`. `output` must be exactly as long as `input`.
+    pub fn calculate_hash_set_into(
+        &self,
+        input: &[&[u8]],
+        output: &mut [[u8; RANDOMX_HASH_SIZE as usize]],
+    ) -> Result<(), RandomXError> {
+        if input.is_empty() || input.iter().any(|item| item.is_empty()) {
             return Err(RandomXError::ParameterError("input was empty".to_string()));
         }
+        if output.len() != input.len() {
+            return Err(RandomXError::ParameterError(format!(
+                "output has room for {} hashes, but there are {} inputs",
+                output.len(),
+                input.len()
+            )));
+        }
 
-        let mut result = Vec::new();
-        // For single input
-        if input.len() == 1 {
-            let hash = self.calculate_hash(input[0])?;
-            result.push(hash);
-            return Ok(result);
+        // The hash of each input is returned by the call that starts hashing the next one
+        unsafe {
+            randomx_calculate_hash_first(self.vm, input[0].as_ptr().cast::<c_void>(), input[0].len());
         }
+        for (item, hash) in input[1..].iter().zip(output.iter_mut()) {
+            unsafe {
+                randomx_calculate_hash_next(
+                    self.vm,
+                    item.as_ptr().cast::<c_void>(),
+                    item.len(),
+                    hash.as_mut_ptr().cast::<c_void>(),
+                );
+            }
+        }
+        unsafe {
+            randomx_calculate_hash_last(self.vm, output[input.len() - 1].as_mut_ptr().cast::<c_void>());
+        }
+        Ok(())
+    }
+}
 
-        // For multiple inputs
-        let mut output_ptr: *mut c_void = ptr::null_mut();
-        let arr = [0; RANDOMX_HASH_SIZE as usize];
+#[cfg(all(test, feature = "ffi"))]
+mod tests {
+    use std::{
+        convert::TryInto,
+        ptr,
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+        },
+        thread,
+    };
 
-        // Not len() as last iteration assigns final hash
-        let iterations = input.len() + 1;
-        for i in 0..iterations {
-            if i == iterations - 1 {
-                // For last iteration
-                unsafe {
-                    randomx_calculate_hash_last(self.vm, output_ptr);
-                }
-            } else {
-                if input[i].is_empty() {
-                    // Stop calculations
-                    if arr != [0; RANDOMX_HASH_SIZE as usize] {
-                        // Complete what was started
-                        unsafe {
-                            randomx_calculate_hash_last(self.vm, output_ptr);
-                        }
-                    }
-                    return Err(RandomXError::ParameterError("input was empty".to_string()));
-                };
-                let size_input = input[i].len();
-                let input_ptr = input[i].as_ptr() as *mut c_void;
-                output_ptr = arr.as_ptr() as *mut c_void;
-                if i == 0 {
-                    // For first iteration
-                    unsafe {
-                        randomx_calculate_hash_first(self.vm, input_ptr, size_input);
+    use crate::{
+        bindings::randomx_get_dataset_memory,
+        CacheHandle,
+        DatasetHandle,
+        RandomXBackend,
+        RandomXCache,
+        RandomXCacheInner,
+        RandomXDataset,
+        RandomXDatasetInner,
+        RandomXError,
+        RandomXFlag,
+        RandomXHash,
+        RandomXVM,
+    };
+
+    /// Runs each of the generic tests against every backend that is compiled in. The `full_mem` tests only run
+    /// against backends that support fast mode, the pure Rust backend is light-mode only.
+    macro_rules! backend_tests {
+        (light: [$($light:ident),* $(,)?], full_mem: [$($full_mem:ident),* $(,)?] $(,)?) => {
+            mod ffi {
+                $(
+                    #[test]
+                    fn $light() {
+                        super::$light(&crate::FfiBackend);
                     }
-                } else {
-                    unsafe {
-                        // For every other iteration
-                        randomx_calculate_hash_next(self.vm, input_ptr, size_input, output_ptr);
+                )*
+                $(
+                    #[test]
+                    fn $full_mem() {
+                        super::$full_mem(&crate::FfiBackend);
                     }
-                }
+                )*
             }
 
-            if i != 0 {
-                // First hash is only available in 2nd iteration
-                if arr == [0; RANDOMX_HASH_SIZE as usize] {
-                    return Err(RandomXError::Other("RandomX hash was zero".to_string()));
-                }
-                let output: Vec<u8> = arr.to_vec();
-                result.push(output);
+            #[cfg(feature = "pure-rust")]
+            mod pure {
+                $(
+                    #[test]
+                    fn $light() {
+                        super::$light(&crate::PureBackend);
+                    }
+                )*
             }
-        }
-        Ok(result)
+        };
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use std::{ptr, sync::Arc};
+    backend_tests!(
+        light: [
+            lib_alloc_cache,
+            lib_alloc_dataset,
+            lib_alloc_vm,
+            lib_calculate_hash_set,
+            lib_calculate_hash_is_consistent,
+            lib_check_cache_and_dataset_lifetimes,
+            test_vectors_light_mode,
+        ],
+        full_mem: [randomx_hash_fast_vs_light, test_vectors_fast_mode],
+    );
 
-    use crate::{RandomXCache, RandomXCacheInner, RandomXDataset, RandomXDatasetInner, RandomXFlag, RandomXVM};
+    /// Creates a dataset from `cache` if the backend supports fast mode.
+    fn dataset_if_supported<B: RandomXBackend>(
+        backend: &B,
+        flags: RandomXFlag,
+        cache: &B::Cache,
+    ) -> Option<B::Dataset> {
+        if backend.supports_full_mem() {
+            Some(backend.create_dataset(flags, cache.clone(), 0).unwrap())
+        } else {
+            None
+        }
+    }
 
-    #[test]
-    fn lib_alloc_cache() {
+    fn lib_alloc_cache<B: RandomXBackend>(backend: &B) {
         let flags = RandomXFlag::default();
         let key = "Key";
-        let cache = RandomXCache::new(flags, key.as_bytes()).expect("Failed to allocate cache");
+        let cache = backend
+            .create_cache(flags, key.as_bytes())
+            .expect("Failed to allocate cache");
         drop(cache);
     }
 
     #[test]
-    fn lib_alloc_dataset() {
+    fn lib_alloc_cache_with_fallback() {
+        let flags = RandomXFlag::get_recommended_flags();
+        let key = "Key";
+        let cache = RandomXCache::new_with_fallback(flags, key.as_bytes()).expect("Failed to allocate cache");
+        if !cache.has_large_pages() {
+            assert!(RandomXCache::new(flags | RandomXFlag::FLAG_LARGE_PAGES, key.as_bytes()).is_err());
+        }
+        assert!(!RandomXCache::new(flags, key.as_bytes()).unwrap().has_large_pages());
+
+        let vm = RandomXVM::new(flags, Some(cache), None).unwrap();
+        let expected = RandomXVM::new(flags, Some(RandomXCache::new(flags, key.as_bytes()).unwrap()), None).unwrap();
+        assert_eq!(
+            vm.calculate_hash(b"Input").unwrap(),
+            expected.calculate_hash(b"Input").unwrap()
+        );
+    }
+
+    fn lib_alloc_dataset<B: RandomXBackend>(backend: &B) {
         let flags = RandomXFlag::default();
         let key = "Key";
-        let cache = RandomXCache::new(flags, key.as_bytes()).unwrap();
-        let dataset = RandomXDataset::new(flags, cache.clone(), 0).expect("Failed to allocate dataset");
+        let cache = backend.create_cache(flags, key.as_bytes()).unwrap();
+        let dataset = backend.create_dataset(flags, cache.clone(), 0);
+        assert_eq!(dataset.is_ok(), backend.supports_full_mem());
         drop(dataset);
         drop(cache);
     }
 
     #[test]
-    fn lib_alloc_vm() {
+    fn lib_alloc_dataset_with_fallback() {
         let flags = RandomXFlag::default();
         let key = "Key";
         let cache = RandomXCache::new(flags, key.as_bytes()).unwrap();
-        let mut vm = RandomXVM::new(flags, Some(cache.clone()), None).expect("Failed to allocate VM");
-        drop(vm);
-        let dataset = RandomXDataset::new(flags, cache.clone(), 0).unwrap();
-        vm = RandomXVM::new(flags, Some(cache.clone()), Some(dataset.clone())).expect("Failed to allocate VM");
-        drop(dataset);
-        drop(cache);
+        let dataset = RandomXDataset::new_with_fallback(flags, cache.clone(), 2).expect("Failed to allocate dataset");
+        if !dataset.has_large_pages() {
+            assert!(RandomXDataset::new(flags | RandomXFlag::FLAG_LARGE_PAGES, cache, 0).is_err());
+        }
+    }
+
+    fn lib_alloc_vm<B: RandomXBackend>(backend: &B) {
+        let flags = RandomXFlag::default();
+        let key = "Key";
+        let cache = backend.create_cache(flags, key.as_bytes()).unwrap();
+        let vm = backend
+            .create_vm(flags, Some(cache.clone()), None)
+            .expect("Failed to allocate VM");
         drop(vm);
+        if let Some(dataset) = dataset_if_supported(backend, flags, &cache) {
+            let vm = backend
+                .create_vm(flags, Some(cache.clone()), Some(dataset.clone()))
+                .expect("Failed to allocate VM");
+            drop(dataset);
+            drop(cache);
+            drop(vm);
+        }
     }
 
     #[test]
@@ -525,33 +1273,100 @@ mod tests {
         let key = "Key";
         let cache = RandomXCache::new(flags, key.as_bytes()).unwrap();
         let dataset = RandomXDataset::new(flags, cache.clone(), 0).unwrap();
-        let memory = dataset.get_data().unwrap_or_else(|_| std::vec::Vec::new());
+        let memory = dataset.memory().unwrap_or_default();
         assert!(!memory.is_empty(), "Failed to get dataset memory");
-        let vec = vec![0u8; memory.len()];
-        assert_ne!(memory, vec);
+        assert_eq!(
+            memory.len(),
+            RandomXDataset::count().unwrap() as usize * RandomXDataset::ITEM_SIZE
+        );
+        assert_eq!(memory.len(), RandomXDataset::memory_size().unwrap());
+        assert!(memory.iter().any(|b| *b != 0));
         drop(dataset);
         drop(cache);
     }
 
+    #[test]
+    fn lib_dataset_items() {
+        let flags = RandomXFlag::default();
+        let key = "Key";
+        let cache = RandomXCache::new(flags, key.as_bytes()).unwrap();
+        let dataset = RandomXDataset::new(flags, cache, 0).unwrap();
+        let count = RandomXDataset::count().unwrap();
+        let memory = dataset.memory().unwrap();
+        for index in [0, 1, count / 2, count - 1] {
+            let start = index as usize * RandomXDataset::ITEM_SIZE;
+            let item = dataset.item(index).unwrap();
+            assert_eq!(&item[..], &memory[start..start + RandomXDataset::ITEM_SIZE]);
+        }
+        assert_ne!(dataset.item(0).unwrap(), dataset.item(1).unwrap());
+        assert!(dataset.item(count).is_err());
+    }
+
     #[test]
     fn test_null_assignments() {
         let flags = RandomXFlag::get_recommended_flags();
-        if let Ok(mut vm) = RandomXVM::new(flags, None, None) {
-            let cache = RandomXCache {
-                inner: Arc::new(RandomXCacheInner {
-                    cache_ptr: ptr::null_mut(),
-                }),
-            };
-            assert!(vm.reinit_cache(cache.clone()).is_err());
-            let dataset = RandomXDataset {
-                inner: Arc::new(RandomXDatasetInner {
-                    dataset_ptr: ptr::null_mut(),
-                    dataset_count: 0,
-                    cache,
-                }),
-            };
-            assert!(vm.reinit_dataset(dataset.clone()).is_err());
+        let mut vm = RandomXVM::new(flags, Some(RandomXCache::new(flags, b"Key").unwrap()), None).unwrap();
+        let cache = RandomXCache {
+            inner: Arc::new(RandomXCacheInner {
+                cache_ptr: ptr::null_mut(),
+                large_pages: false,
+                key: b"Key".to_vec(),
+            }),
+        };
+        assert!(matches!(
+            vm.reinit_cache(cache.clone()),
+            Err(RandomXError::ParameterError(_))
+        ));
+        let dataset = RandomXDataset {
+            inner: Arc::new(RandomXDatasetInner {
+                dataset_ptr: ptr::null_mut(),
+                dataset_count: 0,
+                large_pages: false,
+                cache: Some(cache),
+                #[cfg(target_os = "linux")]
+                shared: None,
+            }),
+        };
+        assert!(matches!(
+            vm.reinit_dataset(dataset),
+            Err(RandomXError::ParameterError(_))
+        ));
+        assert!(vm.calculate_hash(b"Input").is_ok());
+    }
+
+    #[test]
+    fn lib_alloc_vm_with_fallback() {
+        let flags = RandomXFlag::get_recommended_flags();
+        let key = "Key";
+        let cache = RandomXCache::new(flags, key.as_bytes()).unwrap();
+        let vm = RandomXVM::new(flags, Some(cache.clone()), None).unwrap();
+        assert_eq!(vm.flags(), flags);
+
+        let requested = flags | RandomXFlag::FLAG_LARGE_PAGES;
+        let vm = RandomXVM::new_with_fallback(requested, Some(cache.clone()), None).unwrap();
+        assert!(requested.contains(vm.flags()));
+        assert!(vm
+            .flags()
+            .contains(flags - RandomXFlag::FLAG_JIT - RandomXFlag::FLAG_SECURE));
+        if vm.flags() != requested {
+            // Falling back only happens if RandomX could not create the VM with the requested flags
+            assert!(matches!(
+                RandomXVM::new(requested, Some(cache.clone()), None),
+                Err(RandomXError::CreationError(_))
+            ));
         }
+        assert_eq!(
+            vm.calculate_hash(b"Input").unwrap(),
+            RandomXVM::new(flags, Some(cache), None)
+                .unwrap()
+                .calculate_hash(b"Input")
+                .unwrap()
+        );
+
+        assert!(matches!(
+            RandomXVM::new_with_fallback(flags, None, None),
+            Err(RandomXError::CreationError(_))
+        ));
     }
 
     #[test]
@@ -563,7 +1378,7 @@ mod tests {
         let cache1 = RandomXCache::new(flags, key.as_bytes()).unwrap();
         let mut vm1 = RandomXVM::new(flags, Some(cache1.clone()), None).unwrap();
         let hash1 = vm1.calculate_hash(input.as_bytes()).expect("no data");
-        let vec = vec![0u8; hash1.len()];
+        let vec = RandomXHash::default();
         assert_ne!(hash1, vec);
         let reinit_cache = vm1.reinit_cache(cache1.clone());
         assert!(reinit_cache.is_ok());
@@ -605,37 +1420,61 @@ mod tests {
     }
 
     #[test]
-    fn lib_calculate_hash_set() {
+    fn lib_calculate_hash_into() {
+        let flags = RandomXFlag::get_recommended_flags();
+        let cache = RandomXCache::new(flags, b"Key").unwrap();
+        let vm = RandomXVM::new(flags, Some(cache), None).unwrap();
+        let inputs: [&[u8]; 3] = [b"Input", b"Input 2", b"Input 3"];
+
+        let mut output = [0u8; 32];
+        vm.calculate_hash_into(inputs[0], &mut output).unwrap();
+        assert_eq!(output, vm.calculate_hash(inputs[0]).unwrap().into_bytes());
+        assert!(vm.calculate_hash_into(b"", &mut output).is_err());
+
+        let mut outputs = [[0u8; 32]; 3];
+        vm.calculate_hash_set_into(&inputs, &mut outputs).unwrap();
+        for (input, output) in inputs.iter().zip(&outputs) {
+            assert_eq!(*output, vm.calculate_hash(input).unwrap().into_bytes());
+        }
+        assert!(vm.calculate_hash_set_into(&inputs, &mut outputs[..2]).is_err());
+        assert!(vm.calculate_hash_set_into(&inputs[..2], &mut outputs).is_err());
+    }
+
+    fn lib_calculate_hash_set<B: RandomXBackend>(backend: &B) {
         let flags = RandomXFlag::default();
         let key = "Key";
         let inputs = vec!["Input".as_bytes(), "Input 2".as_bytes(), "Inputs 3".as_bytes()];
-        let cache = RandomXCache::new(flags, key.as_bytes()).unwrap();
-        let vm = RandomXVM::new(flags, Some(cache.clone()), None).unwrap();
-        let hashes = vm.calculate_hash_set(inputs.as_slice()).expect("no data");
+        let cache = backend.create_cache(flags, key.as_bytes()).unwrap();
+        let vm = backend.create_vm(flags, Some(cache.clone()), None).unwrap();
+        let hashes = backend.calculate_hash_set(&vm, inputs.as_slice()).expect("no data");
         assert_eq!(inputs.len(), hashes.len());
-        let mut prev_hash = Vec::new();
+        let mut prev_hash = RandomXHash::default();
         for (i, hash) in hashes.into_iter().enumerate() {
-            let vec = vec![0u8; hash.len()];
+            let vec = RandomXHash::default();
             assert_ne!(hash, vec);
             assert_ne!(hash, prev_hash);
-            let compare = vm.calculate_hash(inputs[i]).unwrap(); // sanity check
+            let compare = backend.calculate_hash(&vm, inputs[i]).unwrap(); // sanity check
             assert_eq!(hash, compare);
             prev_hash = hash;
         }
+        assert!(backend.calculate_hash_set(&vm, &[inputs[0], b""]).is_err());
+        assert_eq!(
+            backend.calculate_hash_set(&vm, &inputs[..1]).unwrap()[0],
+            backend.calculate_hash(&vm, inputs[0]).unwrap()
+        );
         drop(cache);
         drop(vm);
     }
 
-    #[test]
-    fn lib_calculate_hash_is_consistent() {
+    fn lib_calculate_hash_is_consistent<B: RandomXBackend>(backend: &B) {
         let flags = RandomXFlag::get_recommended_flags();
         let key = "Key";
         let input = "Input";
-        let cache = RandomXCache::new(flags, key.as_bytes()).unwrap();
-        let dataset = RandomXDataset::new(flags, cache.clone(), 0).unwrap();
-        let vm = RandomXVM::new(flags, Some(cache.clone()), Some(dataset.clone())).unwrap();
-        let hash = vm.calculate_hash(input.as_bytes()).expect("no data");
-        assert_eq!(hash, [
+        let cache = backend.create_cache(flags, key.as_bytes()).unwrap();
+        let dataset = dataset_if_supported(backend, flags, &cache);
+        let vm = backend.create_vm(flags, Some(cache.clone()), dataset.clone()).unwrap();
+        let hash = backend.calculate_hash(&vm, input.as_bytes()).expect("no data");
+        assert_eq!(hash.into_bytes(), [
             114, 81, 192, 5, 165, 242, 107, 100, 184, 77, 37, 129, 52, 203, 217, 227, 65, 83, 215, 213, 59, 71, 32,
             172, 253, 155, 204, 111, 183, 213, 157, 155
         ]);
@@ -643,11 +1482,13 @@ mod tests {
         drop(dataset);
         drop(cache);
 
-        let cache1 = RandomXCache::new(flags, key.as_bytes()).unwrap();
-        let dataset1 = RandomXDataset::new(flags, cache1.clone(), 0).unwrap();
-        let vm1 = RandomXVM::new(flags, Some(cache1.clone()), Some(dataset1.clone())).unwrap();
-        let hash1 = vm1.calculate_hash(input.as_bytes()).expect("no data");
-        assert_eq!(hash1, [
+        let cache1 = backend.create_cache(flags, key.as_bytes()).unwrap();
+        let dataset1 = dataset_if_supported(backend, flags, &cache1);
+        let vm1 = backend
+            .create_vm(flags, Some(cache1.clone()), dataset1.clone())
+            .unwrap();
+        let hash1 = backend.calculate_hash(&vm1, input.as_bytes()).expect("no data");
+        assert_eq!(hash1.into_bytes(), [
             114, 81, 192, 5, 165, 242, 107, 100, 184, 77, 37, 129, 52, 203, 217, 227, 65, 83, 215, 213, 59, 71, 32,
             172, 253, 155, 204, 111, 183, 213, 157, 155
         ]);
@@ -656,30 +1497,31 @@ mod tests {
         drop(cache1);
     }
 
-    #[test]
-    fn lib_check_cache_and_dataset_lifetimes() {
+    fn lib_check_cache_and_dataset_lifetimes<B: RandomXBackend>(backend: &B) {
         let flags = RandomXFlag::get_recommended_flags();
         let key = "Key";
         let input = "Input";
-        let cache = RandomXCache::new(flags, key.as_bytes()).unwrap();
-        let dataset = RandomXDataset::new(flags, cache.clone(), 0).unwrap();
-        let vm = RandomXVM::new(flags, Some(cache.clone()), Some(dataset.clone())).unwrap();
+        let cache = backend.create_cache(flags, key.as_bytes()).unwrap();
+        let dataset = dataset_if_supported(backend, flags, &cache);
+        let vm = backend.create_vm(flags, Some(cache.clone()), dataset.clone()).unwrap();
         drop(dataset);
         drop(cache);
-        let hash = vm.calculate_hash(input.as_bytes()).expect("no data");
-        assert_eq!(hash, [
+        let hash = backend.calculate_hash(&vm, input.as_bytes()).expect("no data");
+        assert_eq!(hash.into_bytes(), [
             114, 81, 192, 5, 165, 242, 107, 100, 184, 77, 37, 129, 52, 203, 217, 227, 65, 83, 215, 213, 59, 71, 32,
             172, 253, 155, 204, 111, 183, 213, 157, 155
         ]);
         drop(vm);
 
-        let cache1 = RandomXCache::new(flags, key.as_bytes()).unwrap();
-        let dataset1 = RandomXDataset::new(flags, cache1.clone(), 0).unwrap();
-        let vm1 = RandomXVM::new(flags, Some(cache1.clone()), Some(dataset1.clone())).unwrap();
+        let cache1 = backend.create_cache(flags, key.as_bytes()).unwrap();
+        let dataset1 = dataset_if_supported(backend, flags, &cache1);
+        let vm1 = backend
+            .create_vm(flags, Some(cache1.clone()), dataset1.clone())
+            .unwrap();
         drop(dataset1);
         drop(cache1);
-        let hash1 = vm1.calculate_hash(input.as_bytes()).expect("no data");
-        assert_eq!(hash1, [
+        let hash1 = backend.calculate_hash(&vm1, input.as_bytes()).expect("no data");
+        assert_eq!(hash1.into_bytes(), [
             114, 81, 192, 5, 165, 242, 107, 100, 184, 77, 37, 129, 52, 203, 217, 227, 65, 83, 215, 213, 59, 71, 32,
             172, 253, 155, 204, 111, 183, 213, 157, 155
         ]);
@@ -687,26 +1529,61 @@ mod tests {
     }
 
     #[test]
-    fn randomx_hash_fast_vs_light() {
+    fn lib_calculate_hash_with_shared_dataset() {
+        let flags = RandomXFlag::get_recommended_flags() | RandomXFlag::FLAG_FULL_MEM;
+        let key = "Key";
+        let inputs = ["Input", "Input 2", "Inputs 3", "Input 4"];
+        let cache = RandomXCache::new(flags, key.as_bytes()).unwrap();
+        let dataset = RandomXDataset::new(flags, cache, 0).unwrap();
+
+        let vm = RandomXVM::new(flags, None, Some(dataset.clone())).unwrap();
+        let expected = inputs
+            .iter()
+            .map(|input| vm.calculate_hash(input.as_bytes()).unwrap())
+            .collect::<Vec<_>>();
+
+        let handles = (0..4)
+            .map(|_| {
+                let dataset = dataset.clone();
+                thread::spawn(move || {
+                    let vm = RandomXVM::new(flags, None, Some(dataset)).unwrap();
+                    inputs
+                        .iter()
+                        .map(|input| vm.calculate_hash(input.as_bytes()).unwrap())
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect::<Vec<_>>();
+        for handle in handles {
+            assert_eq!(handle.join().unwrap(), expected);
+        }
+
+        // A VM can be moved to another thread
+        let hash = thread::spawn(move || vm.calculate_hash(inputs[0].as_bytes()).unwrap())
+            .join()
+            .unwrap();
+        assert_eq!(hash, expected[0]);
+    }
+
+    fn randomx_hash_fast_vs_light<B: RandomXBackend>(backend: &B) {
         let input = b"input";
         let key = b"key";
 
         let flags = RandomXFlag::get_recommended_flags() | RandomXFlag::FLAG_FULL_MEM;
-        let cache = RandomXCache::new(flags, key).unwrap();
-        let dataset = RandomXDataset::new(flags, cache, 0).unwrap();
-        let fast_vm = RandomXVM::new(flags, None, Some(dataset)).unwrap();
+        let cache = backend.create_cache(flags, key).unwrap();
+        let dataset = backend.create_dataset(flags, cache, 0).unwrap();
+        let fast_vm = backend.create_vm(flags, None, Some(dataset)).unwrap();
 
         let flags = RandomXFlag::get_recommended_flags();
-        let cache = RandomXCache::new(flags, key).unwrap();
-        let light_vm = RandomXVM::new(flags, Some(cache), None).unwrap();
+        let cache = backend.create_cache(flags, key).unwrap();
+        let light_vm = backend.create_vm(flags, Some(cache), None).unwrap();
 
-        let fast = fast_vm.calculate_hash(input).unwrap();
-        let light = light_vm.calculate_hash(input).unwrap();
+        let fast = backend.calculate_hash(&fast_vm, input).unwrap();
+        let light = backend.calculate_hash(&light_vm, input).unwrap();
         assert_eq!(fast, light);
     }
 
-    #[test]
-    fn test_vectors_fast_mode() {
+    fn test_vectors_fast_mode<B: RandomXBackend>(backend: &B) {
         // test vectors from https://github.com/tevador/RandomX/blob/040f4500a6e79d54d84a668013a94507045e786f/src/tests/tests.cpp#L963-L979
         let key = b"test key 000";
         let vectors = [
@@ -724,19 +1601,181 @@ mod tests {
             ),
         ];
 
+        let flags = RandomXFlag::get_recommended_flags() | RandomXFlag::FLAG_FULL_MEM;
+        let cache = backend.create_cache(flags, key).unwrap();
+        let dataset = backend.create_dataset(flags, cache, 0).unwrap();
+        let vm = backend.create_vm(flags, None, Some(dataset)).unwrap();
+
+        for (input, expected) in vectors {
+            let hash = backend.calculate_hash(&vm, input).unwrap();
+            assert_eq!(hex::decode(expected).unwrap(), hash.as_ref());
+        }
+    }
+
+    #[test]
+    fn lib_cache_key_and_export() {
+        let flags = RandomXFlag::get_recommended_flags();
+        let cache = RandomXCache::new(flags, b"Key").unwrap();
+        let other_cache = RandomXCache::new(flags, b"Other key").unwrap();
+        assert_eq!(cache.key(), b"Key");
+        assert!(cache == RandomXCache::new(flags, b"Key").unwrap());
+        assert!(cache != other_cache);
+
+        let memory = cache.memory().unwrap();
+        assert_eq!(memory.len(), RandomXCache::MEMORY_SIZE);
+        assert!(memory != other_cache.memory().unwrap());
+        let exported = cache.export().unwrap();
+        assert!(crate::cache_export::decode(b"Key", &exported).unwrap() == memory);
+    }
+
+    #[test]
+    fn lib_dataset_handle_matches_randomx_layout() {
+        let flags = RandomXFlag::get_recommended_flags();
+        let cache = RandomXCache::new(flags, b"Key").unwrap();
+        let dataset = RandomXDataset::new_uninit(flags, cache).unwrap();
+        let handle = unsafe { &*dataset.inner.dataset_ptr.cast::<DatasetHandle>() };
+        let memory = unsafe { randomx_get_dataset_memory(dataset.inner.dataset_ptr) };
+        assert_eq!(handle.memory.cast(), memory);
+    }
+
+    #[test]
+    fn lib_cache_handle_matches_randomx_layout() {
+        let flags = RandomXFlag::get_recommended_flags();
+        let cache = RandomXCache::new(flags, b"Key").unwrap();
+        #[cfg(feature = "pure-rust")]
+        assert!(cache.export().unwrap() == crate::PureRandomXCache::new(b"Key").unwrap().export().unwrap());
+
+        // Dataset items are computed from the memory the handle points to, so changing it changes them
+        let item = cache.compute_dataset_item(0).unwrap();
+        let handle = unsafe { &*cache.inner.cache_ptr.cast::<CacheHandle>() };
+        unsafe { handle.memory.cast_mut().write_bytes(0, RandomXCache::MEMORY_SIZE) };
+        assert!(cache.memory().unwrap().iter().all(|&byte| byte == 0));
+        assert_ne!(cache.compute_dataset_item(0).unwrap(), item);
+    }
+
+    #[test]
+    fn lib_compute_dataset_item_and_verify_sample() {
+        let flags = RandomXFlag::get_recommended_flags();
+        let cache = RandomXCache::new(flags, b"Key").unwrap();
+        let dataset = RandomXDataset::new_parallel(flags, cache.clone(), 4).unwrap();
+        let count = RandomXDataset::count().unwrap();
+        for index in [0, 1, count / 2, count - 1] {
+            assert_eq!(
+                &cache.compute_dataset_item(index).unwrap(),
+                dataset.item(index).unwrap()
+            );
+        }
+        assert!(cache.compute_dataset_item(count).is_err());
+
+        assert!(dataset.verify_sample(32).unwrap());
+        let other_cache = RandomXCache::new(flags, b"Other key").unwrap();
+        assert!(!dataset.verify_sample_with(&other_cache, 8).unwrap());
+    }
+
+    #[test]
+    fn lib_compute_dataset_item_stays_in_bounds() {
+        const GUARD: u8 = 0xa5;
+        let flags = RandomXFlag::get_recommended_flags();
+        let cache = RandomXCache::new(flags, b"Key").unwrap();
+        let count = RandomXDataset::count().unwrap();
+        for index in [0, 1, count / 2, count - 1] {
+            // The item is written between two guard items, which must stay untouched
+            let mut buffer = [GUARD; 3 * RandomXDataset::ITEM_SIZE];
+            let (before, rest) = buffer.split_at_mut(RandomXDataset::ITEM_SIZE);
+            let (item, after) = rest.split_at_mut(RandomXDataset::ITEM_SIZE);
+            cache.init_dataset_item(index, item.try_into().unwrap()).unwrap();
+            assert!(before.iter().chain(after.iter()).all(|&byte| byte == GUARD));
+            assert_eq!(*item, cache.compute_dataset_item(index).unwrap());
+        }
+    }
+
+    #[test]
+    fn lib_dataset_stitched_from_ranges() {
+        let flags = RandomXFlag::get_recommended_flags();
+        let cache = RandomXCache::new(flags, b"Key").unwrap();
+        let count = RandomXDataset::count().unwrap();
+        let half = count / 2;
+
+        // One worker builds the first half, the second half is initialized in place
+        let mut stitched = RandomXDataset::new_uninit(flags, cache.clone()).unwrap();
+        // Items that were not filled yet read as zeroes
+        assert_eq!(stitched.item(0).unwrap(), &[0; RandomXDataset::ITEM_SIZE]);
+        assert!(stitched.items(count - 2, 2).unwrap().iter().all(|&byte| byte == 0));
+        {
+            let mut worker = RandomXDataset::new_uninit(flags, cache.clone()).unwrap();
+            worker.init_range(0, half).unwrap();
+            stitched.write_items(0, worker.items(0, half).unwrap()).unwrap();
+        }
+        stitched.init_range(half, count - half).unwrap();
+
+        assert!(stitched.init_range(count - 1, 2).is_err());
+        assert!(stitched.write_items(count, &[0; RandomXDataset::ITEM_SIZE]).is_err());
+        assert!(stitched.write_items(0, &[0; RandomXDataset::ITEM_SIZE - 1]).is_err());
+        let clone = stitched.clone();
+        assert!(stitched.init_range(0, 1).is_err());
+        drop(clone);
+
+        let single_shot = RandomXDataset::new(flags, cache, 0).unwrap();
+        assert!(stitched.memory().unwrap() == single_shot.memory().unwrap());
+    }
+
+    #[test]
+    fn test_vectors_fast_mode_parallel_dataset() {
+        let key = b"test key 000";
+        let vectors = [
+            (
+                b"This is a test".as_slice(),
+                "639183aae1bf4c9a35884cb46b09cad9175f04efd7684e7262a0ac1c2f0b4e3f",
+            ),
+            (
+                b"Lorem ipsum dolor sit amet".as_slice(),
+                "300a0adb47603dedb42228ccb2b211104f4da45af709cd7547cd049e9489c969",
+            ),
+        ];
+
         let flags = RandomXFlag::get_recommended_flags() | RandomXFlag::FLAG_FULL_MEM;
         let cache = RandomXCache::new(flags, key).unwrap();
-        let dataset = RandomXDataset::new(flags, cache, 0).unwrap();
+        assert!(RandomXDataset::new_parallel(flags, cache.clone(), 0).is_err());
+        let dataset = RandomXDataset::new_parallel(flags, cache, 3).unwrap();
         let vm = RandomXVM::new(flags, None, Some(dataset)).unwrap();
 
         for (input, expected) in vectors {
             let hash = vm.calculate_hash(input).unwrap();
-            assert_eq!(hex::decode(expected).unwrap(), hash);
+            assert_eq!(hex::decode(expected).unwrap(), hash.as_ref());
         }
     }
 
     #[test]
-    fn test_vectors_light_mode() {
+    fn lib_alloc_dataset_with_progress() {
+        let flags = RandomXFlag::default();
+        let key = "Key";
+        let cache = RandomXCache::new(flags, key.as_bytes()).unwrap();
+        let item_count = RandomXDataset::count().unwrap();
+        let chunk_size = item_count / 8 + 1;
+
+        let cancel = AtomicBool::new(false);
+        assert!(RandomXDataset::new_with_progress(flags, cache.clone(), 0, &cancel, |_, _| {}).is_err());
+
+        let mut reports = Vec::new();
+        let dataset = RandomXDataset::new_with_progress(flags, cache.clone(), chunk_size, &cancel, |done, total| {
+            reports.push((done, total))
+        })
+        .expect("Failed to allocate dataset");
+        assert_eq!(reports.len(), 8);
+        assert!(reports.windows(2).all(|w| w[0].0 < w[1].0));
+        assert_eq!(reports.last(), Some(&(item_count, item_count)));
+        drop(dataset);
+
+        let mut reports = Vec::new();
+        let result = RandomXDataset::new_with_progress(flags, cache, chunk_size, &cancel, |done, total| {
+            reports.push((done, total));
+            cancel.store(true, Ordering::Relaxed);
+        });
+        assert!(matches!(result, Err(RandomXError::Cancelled)));
+        assert_eq!(reports, vec![(chunk_size, item_count)]);
+    }
+
+    fn test_vectors_light_mode<B: RandomXBackend>(backend: &B) {
         // test vectors from https://github.com/tevador/RandomX/blob/040f4500a6e79d54d84a668013a94507045e786f/src/tests/tests.cpp#L963-L985
         let vectors = [
             (
@@ -763,10 +1802,10 @@ mod tests {
 
         let flags = RandomXFlag::get_recommended_flags();
         for (key, input, expected) in vectors {
-            let cache = RandomXCache::new(flags, key).unwrap();
-            let vm = RandomXVM::new(flags, Some(cache), None).unwrap();
-            let hash = vm.calculate_hash(input).unwrap();
-            assert_eq!(hex::decode(expected).unwrap(), hash);
+            let cache = backend.create_cache(flags, key).unwrap();
+            let vm = backend.create_vm(flags, Some(cache), None).unwrap();
+            let hash = backend.calculate_hash(&vm, input).unwrap();
+            assert_eq!(hex::decode(expected).unwrap(), hash.as_ref());
         }
     }
 }
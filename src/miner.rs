@@ -0,0 +1,302 @@
+// Copyright 2026. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::{
+    convert::TryFrom,
+    ops::Range,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use libc::c_void;
+
+use crate::{
+    bindings::{
+        randomx_calculate_hash_first,
+        randomx_calculate_hash_last,
+        randomx_calculate_hash_next,
+        RANDOMX_HASH_SIZE,
+    },
+    RandomXError,
+    RandomXHash,
+    RandomXVM,
+};
+
+/// A nonce whose hash met the target of a [`NonceSearch`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FoundNonce {
+    /// The nonce that was written into the template blob.
+    pub nonce: u64,
+    /// The hash of the template blob with the nonce written into it.
+    pub hash: RandomXHash,
+}
+
+/// The result of running a [`NonceSearch`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SearchOutcome {
+    /// The nonces whose hashes met the target, in the order they were found.
+    pub found: Vec<FoundNonce>,
+    /// The number of hashes that were calculated.
+    pub hashes: u64,
+    /// True if the search was interrupted by the stop flag before the whole nonce range was hashed.
+    pub stopped: bool,
+}
+
+/// Searches a range of nonces for hashes that meet a target.
+///
+/// The nonce is written as a little-endian integer of `nonce_width` bytes at `nonce_offset` in the template blob.
+/// Hashes are calculated with the pipelined RandomX functions, so the next input is prepared while the current one is
+/// being hashed.
+#[derive(Debug, Clone)]
+pub struct NonceSearch {
+    blob: Vec<u8>,
+    nonce_offset: usize,
+    nonce_width: usize,
+    nonces: Range<u64>,
+    target: [u8; RANDOMX_HASH_SIZE as usize],
+}
+
+impl NonceSearch {
+    /// Creates a new nonce search, error on invalid parameters.
+    ///
+    /// `blob` is the template to be hashed, e.g. a block hashing blob.
+    ///
+    /// `nonce_offset` is the byte offset of the nonce in `blob`.
+    ///
+    /// `nonce_width` is the size of the nonce in bytes, between 1 and 8.
+    ///
+    /// `nonces` is the range of nonces to search. Every nonce in the range must fit into `nonce_width` bytes.
+    ///
+    /// `target` is the little-endian target a hash must not exceed, see
+    /// [`difficulty_to_target`](crate::difficulty_to_target).
+    pub fn new(
+        blob: Vec<u8>,
+        nonce_offset: usize,
+        nonce_width: usize,
+        nonces: Range<u64>,
+        target: [u8; RANDOMX_HASH_SIZE as usize],
+    ) -> Result<NonceSearch, RandomXError> {
+        if nonce_width == 0 || nonce_width > 8 {
+            return Err(RandomXError::ParameterError(format!(
+                "nonce width must be between 1 and 8 bytes, got {nonce_width}"
+            )));
+        }
+        if !matches!(nonce_offset.checked_add(nonce_width), Some(end) if end <= blob.len()) {
+            return Err(RandomXError::ParameterError(format!(
+                "nonce at offset {nonce_offset} with width {nonce_width} does not fit into blob of {} bytes",
+                blob.len()
+            )));
+        }
+        let max_nonce = u64::MAX >> (64 - 8 * u32::try_from(nonce_width)?);
+        if nonces.end > 0 && nonces.end - 1 > max_nonce {
+            return Err(RandomXError::ParameterError(format!(
+                "nonce range {nonces:?} does not fit into {nonce_width} bytes"
+            )));
+        }
+        Ok(NonceSearch {
+            blob,
+            nonce_offset,
+            nonce_width,
+            nonces,
+            target,
+        })
+    }
+
+    /// Hashes every nonce in the range with `vm` and returns the nonces that met the target.
+    ///
+    /// `stop` is checked before every hash. Once it is set, the search finishes the hash in flight and returns what it
+    /// found so far, with `SearchOutcome::stopped` set.
+    pub fn search(&self, vm: &mut RandomXVM, stop: &AtomicBool) -> SearchOutcome {
+        let mut outcome = SearchOutcome::default();
+        let mut nonces = self.nonces.clone();
+        let mut pending = match nonces.next() {
+            Some(nonce) => nonce,
+            None => return outcome,
+        };
+        if stop.load(Ordering::Relaxed) {
+            outcome.stopped = true;
+            return outcome;
+        }
+
+        let mut blob = self.blob.clone();
+        let mut output = [0u8; RANDOMX_HASH_SIZE as usize];
+        self.write_nonce(&mut blob, pending);
+        unsafe {
+            randomx_calculate_hash_first(vm.vm, blob.as_ptr().cast::<c_void>(), blob.len());
+        }
+        for nonce in nonces {
+            if stop.load(Ordering::Relaxed) {
+                outcome.stopped = true;
+                break;
+            }
+            self.write_nonce(&mut blob, nonce);
+            unsafe {
+                randomx_calculate_hash_next(
+                    vm.vm,
+                    blob.as_ptr().cast::<c_void>(),
+                    blob.len(),
+                    output.as_mut_ptr().cast::<c_void>(),
+                );
+            }
+            self.check(pending, output, &mut outcome);
+            pending = nonce;
+        }
+        unsafe {
+            randomx_calculate_hash_last(vm.vm, output.as_mut_ptr().cast::<c_void>());
+        }
+        self.check(pending, output, &mut outcome);
+        outcome
+    }
+
+    fn write_nonce(&self, blob: &mut [u8], nonce: u64) {
+        blob[self.nonce_offset..self.nonce_offset + self.nonce_width]
+            .copy_from_slice(&nonce.to_le_bytes()[..self.nonce_width]);
+    }
+
+    fn check(&self, nonce: u64, output: [u8; RANDOMX_HASH_SIZE as usize], outcome: &mut SearchOutcome) {
+        let hash = RandomXHash::from(output);
+        outcome.hashes += 1;
+        if hash.meets_target(&self.target) {
+            outcome.found.push(FoundNonce { nonce, hash });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::atomic::{AtomicBool, Ordering},
+        thread,
+        time::Duration,
+    };
+
+    use crate::{difficulty_to_target, NonceSearch, RandomXCache, RandomXFlag, RandomXVM};
+
+    fn blob_with_nonce(blob: &[u8], offset: usize, nonce: u32) -> Vec<u8> {
+        let mut blob = blob.to_vec();
+        blob[offset..offset + 4].copy_from_slice(&nonce.to_le_bytes());
+        blob
+    }
+
+    #[test]
+    fn nonce_search_parameters() {
+        let target = difficulty_to_target(1);
+        assert!(NonceSearch::new(vec![0; 76], 39, 0, 0..10, target).is_err());
+        assert!(NonceSearch::new(vec![0; 76], 39, 9, 0..10, target).is_err());
+        assert!(NonceSearch::new(vec![0; 76], 73, 4, 0..10, target).is_err());
+        assert!(NonceSearch::new(vec![0; 76], usize::MAX, 4, 0..10, target).is_err());
+        assert!(NonceSearch::new(vec![0; 76], 72, 4, 0..10, target).is_ok());
+        assert!(NonceSearch::new(vec![0; 76], 39, 1, 0..256, target).is_ok());
+        assert!(NonceSearch::new(vec![0; 76], 39, 1, 0..257, target).is_err());
+        assert!(NonceSearch::new(vec![0; 76], 39, 8, 0..u64::MAX, target).is_ok());
+    }
+
+    #[test]
+    fn nonce_search_finds_nonces() {
+        let flags = RandomXFlag::get_recommended_flags();
+        let cache = RandomXCache::new(flags, b"Key").unwrap();
+        let mut vm = RandomXVM::new(flags, Some(cache), None).unwrap();
+        let blob = vec![7u8; 76];
+        let stop = AtomicBool::new(false);
+
+        // Every hash meets a difficulty of 1
+        let search = NonceSearch::new(blob.clone(), 39, 4, 10..16, difficulty_to_target(1)).unwrap();
+        let outcome = search.search(&mut vm, &stop);
+        assert!(!outcome.stopped);
+        assert_eq!(outcome.hashes, 6);
+        assert_eq!(outcome.found.len(), 6);
+        for (found, nonce) in outcome.found.iter().zip(10u32..16) {
+            assert_eq!(found.nonce, u64::from(nonce));
+            let expected = vm.calculate_hash(&blob_with_nonce(&blob, 39, nonce)).unwrap();
+            assert_eq!(found.hash, expected);
+        }
+
+        // A single nonce only goes through the first and last steps of the pipeline
+        let search = NonceSearch::new(blob.clone(), 39, 4, 3..4, difficulty_to_target(1)).unwrap();
+        let outcome = search.search(&mut vm, &stop);
+        assert_eq!(outcome.found.len(), 1);
+        assert_eq!(
+            outcome.found[0].hash,
+            vm.calculate_hash(&blob_with_nonce(&blob, 39, 3)).unwrap()
+        );
+
+        // Only the hashes that meet the difficulty are reported
+        let difficulty = 4;
+        let search = NonceSearch::new(blob.clone(), 39, 4, 0..32, difficulty_to_target(difficulty)).unwrap();
+        let outcome = search.search(&mut vm, &stop);
+        assert_eq!(outcome.hashes, 32);
+        let expected = (0u32..32)
+            .filter(|nonce| {
+                vm.calculate_hash(&blob_with_nonce(&blob, 39, *nonce))
+                    .unwrap()
+                    .meets_difficulty(difficulty)
+            })
+            .map(u64::from)
+            .collect::<Vec<_>>();
+        assert_eq!(outcome.found.iter().map(|f| f.nonce).collect::<Vec<_>>(), expected);
+
+        let search = NonceSearch::new(blob, 39, 4, 0..0, difficulty_to_target(1)).unwrap();
+        assert_eq!(search.search(&mut vm, &stop).hashes, 0);
+    }
+
+    #[test]
+    fn nonce_search_stops() {
+        let flags = RandomXFlag::get_recommended_flags();
+        let cache = RandomXCache::new(flags, b"Key").unwrap();
+        let mut vm = RandomXVM::new(flags, Some(cache), None).unwrap();
+        let stop = AtomicBool::new(true);
+
+        let search = NonceSearch::new(vec![7u8; 76], 39, 4, 0..1000, difficulty_to_target(1)).unwrap();
+        let outcome = search.search(&mut vm, &stop);
+        assert!(outcome.stopped);
+        assert_eq!(outcome.hashes, 0);
+        assert!(outcome.found.is_empty());
+
+        // The VM is still usable after an interrupted search
+        assert!(vm.calculate_hash(b"Input").is_ok());
+    }
+
+    #[test]
+    fn nonce_search_stops_while_running() {
+        let flags = RandomXFlag::get_recommended_flags();
+        let cache = RandomXCache::new(flags, b"Key").unwrap();
+        let mut vm = RandomXVM::new(flags, Some(cache), None).unwrap();
+        let stop = AtomicBool::new(false);
+
+        // The range is far too large to finish, so only the stop flag ends the search
+        let search = NonceSearch::new(vec![7u8; 76], 39, 8, 0..u64::MAX, difficulty_to_target(1)).unwrap();
+        let outcome = thread::scope(|scope| {
+            scope.spawn(|| {
+                thread::sleep(Duration::from_millis(200));
+                stop.store(true, Ordering::Relaxed);
+            });
+            search.search(&mut vm, &stop)
+        });
+        assert!(outcome.stopped);
+        assert!(outcome.hashes > 0);
+        // Every hash meets a difficulty of 1, so the partial result holds the nonces hashed so far, in order
+        assert_eq!(outcome.found.len() as u64, outcome.hashes);
+        for (found, nonce) in outcome.found.iter().zip(0u64..) {
+            assert_eq!(found.nonce, nonce);
+        }
+        assert!(vm.calculate_hash(b"Input").is_ok());
+    }
+}
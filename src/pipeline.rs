@@ -0,0 +1,135 @@
+// Copyright 2026. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use libc::c_void;
+
+use crate::{
+    bindings::{
+        randomx_calculate_hash_first,
+        randomx_calculate_hash_last,
+        randomx_calculate_hash_next,
+        RANDOMX_HASH_SIZE,
+    },
+    RandomXError,
+    RandomXHash,
+    RandomXVM,
+};
+
+/// Calculates hashes of a stream of inputs on one VM, overlapping the work on each input with the next.
+///
+/// The pipeline is opened with the first input. The hash of an input becomes available when the following input is
+/// pushed, or when the pipeline is finished. The pipeline borrows the VM mutably, so nothing else can use the VM until
+/// the pipeline is dropped.
+///
+/// ```no_run
+/// # use randomx_rs::{RandomXCache, RandomXFlag, RandomXVM};
+/// # let flags = RandomXFlag::get_recommended_flags();
+/// # let cache = RandomXCache::new(flags, b"key").unwrap();
+/// let mut vm = RandomXVM::new(flags, Some(cache), None).unwrap();
+/// let mut pipeline = vm.hash_pipeline(&0u32.to_le_bytes()).unwrap();
+/// let mut hashes = Vec::new();
+/// for nonce in 1u32..4 {
+///     hashes.push(pipeline.push(&nonce.to_le_bytes()).unwrap());
+/// }
+/// hashes.push(pipeline.finish());
+/// assert_eq!(hashes.len(), 4);
+/// ```
+#[derive(Debug)]
+pub struct HashPipeline<'a> {
+    vm: &'a mut RandomXVM,
+}
+
+impl<'a> HashPipeline<'a> {
+    /// Starts hashing `input`, error if it is empty.
+    pub(crate) fn new(vm: &'a mut RandomXVM, input: &[u8]) -> Result<Self, RandomXError> {
+        check_input(input)?;
+        unsafe {
+            randomx_calculate_hash_first(vm.vm, input.as_ptr().cast::<c_void>(), input.len());
+        }
+        Ok(Self { vm })
+    }
+
+    /// Starts hashing `input` and returns the hash of the previously pushed input, error if `input` is empty.
+    pub fn push(&mut self, input: &[u8]) -> Result<RandomXHash, RandomXError> {
+        check_input(input)?;
+        let mut output = [0u8; RANDOMX_HASH_SIZE as usize];
+        unsafe {
+            randomx_calculate_hash_next(
+                self.vm.vm,
+                input.as_ptr().cast::<c_void>(),
+                input.len(),
+                output.as_mut_ptr().cast::<c_void>(),
+            );
+        }
+        Ok(RandomXHash::from(output))
+    }
+
+    /// Returns the hash of the last pushed input.
+    pub fn finish(self) -> RandomXHash {
+        let mut output = [0u8; RANDOMX_HASH_SIZE as usize];
+        unsafe {
+            randomx_calculate_hash_last(self.vm.vm, output.as_mut_ptr().cast::<c_void>());
+        }
+        RandomXHash::from(output)
+    }
+}
+
+fn check_input(input: &[u8]) -> Result<(), RandomXError> {
+    if input.is_empty() {
+        return Err(RandomXError::ParameterError("input was empty".to_string()));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{RandomXCache, RandomXError, RandomXFlag, RandomXVM};
+
+    #[test]
+    fn pipeline_matches_single_hashes() {
+        let flags = RandomXFlag::get_recommended_flags();
+        let cache = RandomXCache::new(flags, b"RandomX example key").unwrap();
+        let mut vm = RandomXVM::new(flags, Some(cache), None).unwrap();
+        let inputs: Vec<[u8; 4]> = (0u32..5).map(u32::to_le_bytes).collect();
+        let expected = inputs
+            .iter()
+            .map(|input| vm.calculate_hash(input).unwrap())
+            .collect::<Vec<_>>();
+
+        let mut pipeline = vm.hash_pipeline(&inputs[0]).unwrap();
+        let mut hashes = inputs[1..]
+            .iter()
+            .map(|input| pipeline.push(input).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(hashes.len(), inputs.len() - 1);
+        hashes.push(pipeline.finish());
+        assert_eq!(hashes, expected);
+
+        assert_eq!(vm.hash_pipeline(&inputs[0]).unwrap().finish(), expected[0]);
+
+        assert!(matches!(vm.hash_pipeline(&[]), Err(RandomXError::ParameterError(_))));
+        let mut pipeline = vm.hash_pipeline(&inputs[0]).unwrap();
+        assert!(matches!(pipeline.push(&[]), Err(RandomXError::ParameterError(_))));
+        assert_eq!(pipeline.push(&inputs[1]).unwrap(), expected[0]);
+        assert_eq!(pipeline.finish(), expected[1]);
+    }
+}
@@ -0,0 +1,154 @@
+// Copyright 2026. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! The AES-based generators and hash used to fill the scratchpad, generate programs and fingerprint the scratchpad.
+//! Round keys are listed the way `_mm_set_epi32` takes them, most significant dword first.
+
+use aes::{
+    hazmat::{cipher_round, equiv_inv_cipher_round},
+    Block,
+};
+
+const GEN_1R_KEYS: [[u32; 4]; 4] = [
+    [0xb4f44917, 0xdbb5552b, 0x62716609, 0x6daca553],
+    [0x0da1dc4e, 0x1725d378, 0x846a710d, 0x6d7caf07],
+    [0x3e20e345, 0xf4c0794f, 0x9f947ec6, 0x3f1262f1],
+    [0x49169154, 0x16314c88, 0xb1ba317c, 0x6aef8135],
+];
+
+const GEN_4R_KEYS: [[u32; 4]; 8] = [
+    [0x99e5d23f, 0x2f546d2b, 0xd1833ddb, 0x6421aadd],
+    [0xa5dfcde5, 0x06f79d53, 0xb6913f55, 0xb20e3450],
+    [0x171c02bf, 0x0aa4679f, 0x515e7baf, 0x5c3ed904],
+    [0xd8ded291, 0xcd673785, 0xe78f5d08, 0x85623763],
+    [0x229effb4, 0x3d518b6d, 0xe3d6a7a6, 0xb5826f73],
+    [0xb272b7d2, 0xe9024d4e, 0x9c10b3d9, 0xc7566bf3],
+    [0xf63befa7, 0x2ba9660a, 0xf765a38b, 0xf273c9e7],
+    [0xc0b0762d, 0x0c06d1fd, 0x915839de, 0x7a7cd609],
+];
+
+const HASH_1R_STATE: [[u32; 4]; 4] = [
+    [0xd7983aad, 0xcc82db47, 0x9fa856de, 0x92b52c0d],
+    [0xace78057, 0xf59e125a, 0x15c7b798, 0x338d996e],
+    [0xe8a07ce4, 0x5079506b, 0xae62c7d0, 0x6a770017],
+    [0x7e994948, 0x79a10005, 0x07ad828d, 0x630a240c],
+];
+
+const HASH_1R_XKEYS: [[u32; 4]; 2] = [[0x06890201, 0x90dc56bf, 0x8b24949f, 0xf6fa8389], [
+    0xed18f99b, 0xee1043c6, 0x51f4e03c, 0x61b263d1,
+]];
+
+fn block(dwords: &[u32; 4]) -> Block {
+    let mut block = Block::default();
+    for (chunk, dword) in block.chunks_exact_mut(4).zip(dwords.iter().rev()) {
+        chunk.copy_from_slice(&dword.to_le_bytes());
+    }
+    block
+}
+
+fn load(state: &[u8]) -> [Block; 4] {
+    let mut blocks = [Block::default(); 4];
+    for (block, chunk) in blocks.iter_mut().zip(state.chunks_exact(16)) {
+        block.copy_from_slice(chunk);
+    }
+    blocks
+}
+
+fn store(blocks: &[Block; 4], out: &mut [u8]) {
+    for (block, chunk) in blocks.iter().zip(out.chunks_exact_mut(16)) {
+        chunk.copy_from_slice(block);
+    }
+}
+
+fn enc(state: &mut Block, key: &Block) {
+    cipher_round(state, key);
+}
+
+fn dec(state: &mut Block, key: &Block) {
+    equiv_inv_cipher_round(state, key);
+}
+
+/// Fills `out` (a multiple of 64 bytes) from the 64-byte `state` with one AES round per column, writing the final
+/// state back.
+pub(crate) fn fill_aes_1rx4(state: &mut [u8; 64], out: &mut [u8]) {
+    let keys = [
+        block(&GEN_1R_KEYS[0]),
+        block(&GEN_1R_KEYS[1]),
+        block(&GEN_1R_KEYS[2]),
+        block(&GEN_1R_KEYS[3]),
+    ];
+    let mut s = load(state);
+    for chunk in out.chunks_exact_mut(64) {
+        dec(&mut s[0], &keys[0]);
+        enc(&mut s[1], &keys[1]);
+        dec(&mut s[2], &keys[2]);
+        enc(&mut s[3], &keys[3]);
+        store(&s, chunk);
+    }
+    store(&s, state);
+}
+
+/// Fills `out` (a multiple of 64 bytes) from the 64-byte `state` with four AES rounds per column. The state is left
+/// untouched.
+pub(crate) fn fill_aes_4rx4(state: &[u8; 64], out: &mut [u8]) {
+    let mut keys = [Block::default(); 8];
+    for (key, dwords) in keys.iter_mut().zip(GEN_4R_KEYS.iter()) {
+        *key = block(dwords);
+    }
+    let mut s = load(state);
+    for chunk in out.chunks_exact_mut(64) {
+        for round in 0..4 {
+            dec(&mut s[0], &keys[round]);
+            enc(&mut s[1], &keys[round]);
+            dec(&mut s[2], &keys[round + 4]);
+            enc(&mut s[3], &keys[round + 4]);
+        }
+        store(&s, chunk);
+    }
+}
+
+/// Hashes `input` (a multiple of 64 bytes) into 64 bytes.
+pub(crate) fn hash_aes_1rx4(input: &[u8]) -> [u8; 64] {
+    let mut s = [
+        block(&HASH_1R_STATE[0]),
+        block(&HASH_1R_STATE[1]),
+        block(&HASH_1R_STATE[2]),
+        block(&HASH_1R_STATE[3]),
+    ];
+    for chunk in input.chunks_exact(64) {
+        let columns = load(chunk);
+        enc(&mut s[0], &columns[0]);
+        dec(&mut s[1], &columns[1]);
+        enc(&mut s[2], &columns[2]);
+        dec(&mut s[3], &columns[3]);
+    }
+    for xkey in &HASH_1R_XKEYS {
+        let xkey = block(xkey);
+        enc(&mut s[0], &xkey);
+        dec(&mut s[1], &xkey);
+        enc(&mut s[2], &xkey);
+        dec(&mut s[3], &xkey);
+    }
+    let mut out = [0u8; 64];
+    store(&s, &mut out);
+    out
+}
@@ -0,0 +1,68 @@
+// Copyright 2026. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use blake2::{Blake2b512, Digest};
+
+const MAX_SEED_SIZE: usize = 60;
+
+/// Deterministic byte stream used to generate superscalar programs, re-hashing its 64-byte state with Blake2b
+/// whenever it runs dry.
+pub(crate) struct Blake2Generator {
+    data: [u8; 64],
+    index: usize,
+}
+
+impl Blake2Generator {
+    pub fn new(seed: &[u8], nonce: u32) -> Self {
+        let mut data = [0u8; 64];
+        let len = seed.len().min(MAX_SEED_SIZE);
+        data[..len].copy_from_slice(&seed[..len]);
+        data[MAX_SEED_SIZE..].copy_from_slice(&nonce.to_le_bytes());
+        Self {
+            data,
+            index: data.len(),
+        }
+    }
+
+    pub fn get_byte(&mut self) -> u8 {
+        self.check_data(1);
+        let byte = self.data[self.index];
+        self.index += 1;
+        byte
+    }
+
+    pub fn get_u32(&mut self) -> u32 {
+        self.check_data(4);
+        let mut bytes = [0u8; 4];
+        bytes.copy_from_slice(&self.data[self.index..self.index + 4]);
+        self.index += 4;
+        u32::from_le_bytes(bytes)
+    }
+
+    fn check_data(&mut self, needed: usize) {
+        if self.index + needed > self.data.len() {
+            let digest = Blake2b512::digest(self.data);
+            self.data.copy_from_slice(&digest);
+            self.index = 0;
+        }
+    }
+}
@@ -0,0 +1,123 @@
+// Copyright 2026. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use argon2::{Algorithm, Argon2, Block, Params, Version};
+
+use super::{blake2_generator::Blake2Generator, superscalar::SuperscalarProgram};
+use crate::{bindings::RANDOMX_ARGON_MEMORY, RandomXError};
+
+const ARGON_ITERATIONS: u32 = 3;
+const ARGON_LANES: u32 = 1;
+const ARGON_SALT: &[u8] = b"RandomX\x03";
+const CACHE_ACCESSES: usize = 8;
+/// Number of 64-byte lines in the cache.
+const CACHE_LINE_COUNT: u64 = RANDOMX_ARGON_MEMORY as u64 * 1024 / 64;
+
+const SUPERSCALAR_MUL0: u64 = 6_364_136_223_846_793_005;
+const SUPERSCALAR_ADD: [u64; 7] = [
+    9_298_411_001_130_361_340,
+    12_065_312_585_734_608_966,
+    9_306_329_213_124_626_780,
+    5_281_919_268_842_080_866,
+    10_536_153_434_571_861_004,
+    3_398_623_926_847_679_864,
+    9_549_104_520_008_361_294,
+];
+
+/// The Argon2d-filled cache memory and the SuperscalarHash programs derived from the same key.
+pub(crate) struct PureRandomXCacheInner {
+    key: Vec<u8>,
+    memory: Vec<Block>,
+    programs: Vec<SuperscalarProgram>,
+}
+
+impl PureRandomXCacheInner {
+    pub fn new(key: &[u8]) -> Result<Self, RandomXError> {
+        let params = Params::new(RANDOMX_ARGON_MEMORY, ARGON_ITERATIONS, ARGON_LANES, None)
+            .map_err(|e| RandomXError::CreationError(format!("Invalid Argon2 parameters: {e}")))?;
+        let argon2 = Argon2::new(Algorithm::Argon2d, Version::V0x13, params);
+        let mut memory = vec![Block::default(); RANDOMX_ARGON_MEMORY as usize];
+        argon2
+            .fill_memory(key, ARGON_SALT, &mut memory)
+            .map_err(|e| RandomXError::CreationError(format!("Could not fill cache: {e}")))?;
+        Ok(Self::with_memory(key, memory))
+    }
+
+    /// Rebuilds a cache from memory that was filled for `key`, e.g. by a previous `new`, skipping Argon2d. `bytes`
+    /// holds the blocks as little-endian 64-bit words.
+    pub fn from_memory(key: &[u8], bytes: &[u8]) -> Self {
+        let mut memory = vec![Block::default(); RANDOMX_ARGON_MEMORY as usize];
+        for (block, chunk) in memory.iter_mut().zip(bytes.chunks_exact(Block::SIZE)) {
+            for (word, word_bytes) in block.as_mut().iter_mut().zip(chunk.chunks_exact(8)) {
+                let mut le = [0u8; 8];
+                le.copy_from_slice(word_bytes);
+                *word = u64::from_le_bytes(le);
+            }
+        }
+        Self::with_memory(key, memory)
+    }
+
+    fn with_memory(key: &[u8], memory: Vec<Block>) -> Self {
+        let mut gen = Blake2Generator::new(key, 0);
+        let programs = (0..CACHE_ACCESSES)
+            .map(|_| SuperscalarProgram::generate(&mut gen))
+            .collect();
+        Self {
+            key: key.to_vec(),
+            memory,
+            programs,
+        }
+    }
+
+    pub fn key(&self) -> &[u8] {
+        &self.key
+    }
+
+    /// Returns the cache memory as little-endian 64-bit words.
+    pub fn memory_bytes(&self) -> Vec<u8> {
+        self.memory
+            .iter()
+            .flat_map(|block| block.as_ref().iter().flat_map(|word| word.to_le_bytes()))
+            .collect()
+    }
+
+    /// Computes the 64-byte dataset item `item_number` from the cache.
+    pub fn dataset_item(&self, item_number: u64) -> [u64; 8] {
+        let r0 = item_number.wrapping_add(1).wrapping_mul(SUPERSCALAR_MUL0);
+        let mut r = [r0; 8];
+        for (reg, add) in r[1..].iter_mut().zip(SUPERSCALAR_ADD.iter()) {
+            *reg ^= add;
+        }
+        let mut register_value = item_number;
+        for program in &self.programs {
+            let line = register_value & (CACHE_LINE_COUNT - 1);
+            program.execute(&mut r);
+            let block = &self.memory[(line / 16) as usize];
+            let offset = (line % 16) as usize * 8;
+            for (reg, word) in r.iter_mut().zip(&block.as_ref()[offset..offset + 8]) {
+                *reg ^= word;
+            }
+            register_value = r[program.address_register()];
+        }
+        r
+    }
+}
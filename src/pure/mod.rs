@@ -0,0 +1,330 @@
+// Copyright 2026. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! A pure-Rust implementation of RandomX light-mode hashing, enabled by the `pure-rust` feature.
+//!
+//! It produces the same hashes as a [`RandomXVM`](crate::RandomXVM) created without `FLAG_FULL_MEM`, using only the
+//! cache, but runs the programs in an interpreter. It is meant for verifiers that only hash a handful of inputs,
+//! and is far slower than the JIT compiled C implementation.
+
+// RandomX is specified in terms of wrapping and truncating integer conversions
+#![allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap, clippy::cast_sign_loss)]
+
+mod aes;
+mod blake2_generator;
+mod cache;
+mod superscalar;
+mod vm;
+
+use std::{convert::Infallible, sync::Arc};
+
+use blake2::{digest::consts::U32, Blake2b, Blake2b512, Digest};
+
+use self::{cache::PureRandomXCacheInner, vm::Machine};
+use crate::{cache_export, RandomXBackend, RandomXError, RandomXFlag, RandomXHash};
+
+const PROGRAM_COUNT: usize = 8;
+
+#[derive(Clone)]
+/// A light-mode cache built without the C library. Cloning is cheap and shares the cache memory.
+///
+/// Caches compare equal if they were initialized with the same key.
+pub struct PureRandomXCache {
+    inner: Arc<PureRandomXCacheInner>,
+}
+
+impl PureRandomXCache {
+    /// Fills a new 256 MiB cache from `key` with Argon2d and generates its SuperscalarHash programs.
+    pub fn new(key: &[u8]) -> Result<PureRandomXCache, RandomXError> {
+        if key.is_empty() {
+            return Err(RandomXError::ParameterError("key is empty".to_string()));
+        }
+        Ok(PureRandomXCache {
+            inner: Arc::new(PureRandomXCacheInner::new(key)?),
+        })
+    }
+
+    /// Returns the key the cache was initialized with.
+    pub fn key(&self) -> &[u8] {
+        self.inner.key()
+    }
+
+    /// Exports the key and memory of the cache, with a checksum, in the same format as `RandomXCache::export`.
+    pub fn export(&self) -> Result<Vec<u8>, RandomXError> {
+        cache_export::encode(self.key(), &self.inner.memory_bytes())
+    }
+
+    /// Rebuilds a cache for `key` from an export of [`PureRandomXCache::export`] or `RandomXCache::export`, skipping
+    /// Argon2d.
+    ///
+    /// `RandomXError::CacheExportMismatch` is returned if `bytes` were exported for another key, and
+    /// `RandomXError::CacheExportCorrupt` if they are damaged.
+    pub fn import(key: &[u8], bytes: &[u8]) -> Result<PureRandomXCache, RandomXError> {
+        if key.is_empty() {
+            return Err(RandomXError::ParameterError("key is empty".to_string()));
+        }
+        let memory = cache_export::decode(key, bytes)?;
+        Ok(PureRandomXCache {
+            inner: Arc::new(PureRandomXCacheInner::from_memory(key, memory)),
+        })
+    }
+}
+
+impl PartialEq for PureRandomXCache {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for PureRandomXCache {}
+
+impl std::fmt::Debug for PureRandomXCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PureRandomXCache").finish_non_exhaustive()
+    }
+}
+
+#[derive(Debug, Clone)]
+/// A light-mode VM that interprets RandomX programs in Rust.
+pub struct PureRandomXVM {
+    cache: PureRandomXCache,
+}
+
+impl PureRandomXVM {
+    /// Creates a new `VM` that computes dataset items from `cache` on demand.
+    pub fn new(cache: PureRandomXCache) -> PureRandomXVM {
+        PureRandomXVM { cache }
+    }
+
+    /// Replaces the cache, for example after a key change.
+    pub fn reinit_cache(&mut self, cache: PureRandomXCache) {
+        self.cache = cache;
+    }
+
+    /// Calculates a RandomX hash value and returns it, error on failure.
+    ///
+    /// `input` is a sequence of u8 to be hashed.
+    pub fn calculate_hash(&self, input: &[u8]) -> Result<RandomXHash, RandomXError> {
+        if input.is_empty() {
+            return Err(RandomXError::ParameterError("input was empty".to_string()));
+        }
+        let mut seed = blake2b_512(input);
+        let register_file = Machine::new(&self.cache.inner).run_chain(&mut seed, PROGRAM_COUNT);
+        let mut hash = [0u8; 32];
+        hash.copy_from_slice(&Blake2b::<U32>::digest(register_file));
+        Ok(RandomXHash::from(hash))
+    }
+}
+
+/// The pure-Rust implementation as a [`RandomXBackend`]. It only supports light mode, so it cannot create datasets
+/// and ignores all flags except FLAG_FULL_MEM, which it rejects.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PureBackend;
+
+impl RandomXBackend for PureBackend {
+    type Cache = PureRandomXCache;
+    type Dataset = Infallible;
+    type VM = PureRandomXVM;
+
+    fn supports_full_mem(&self) -> bool {
+        false
+    }
+
+    fn create_cache(&self, _flags: RandomXFlag, key: &[u8]) -> Result<PureRandomXCache, RandomXError> {
+        PureRandomXCache::new(key)
+    }
+
+    fn create_dataset(
+        &self,
+        _flags: RandomXFlag,
+        _cache: PureRandomXCache,
+        _start: u32,
+    ) -> Result<Infallible, RandomXError> {
+        Err(RandomXError::FlagConfigError(
+            "The pure-Rust backend does not support datasets".to_string(),
+        ))
+    }
+
+    fn create_vm(
+        &self,
+        flags: RandomXFlag,
+        cache: Option<PureRandomXCache>,
+        _dataset: Option<Infallible>,
+    ) -> Result<PureRandomXVM, RandomXError> {
+        if flags.contains(RandomXFlag::FLAG_FULL_MEM) {
+            return Err(RandomXError::FlagConfigError(
+                "The pure-Rust backend does not support FLAG_FULL_MEM".to_string(),
+            ));
+        }
+        cache
+            .map(PureRandomXVM::new)
+            .ok_or_else(|| RandomXError::CreationError("A cache is required to create a VM".to_string()))
+    }
+
+    fn calculate_hash(&self, vm: &PureRandomXVM, input: &[u8]) -> Result<RandomXHash, RandomXError> {
+        vm.calculate_hash(input)
+    }
+}
+
+fn blake2b_512(input: &[u8]) -> [u8; 64] {
+    let mut out = [0u8; 64];
+    out.copy_from_slice(&Blake2b512::digest(input));
+    out
+}
+
+fn sign_extend(imm32: u32) -> u64 {
+    i64::from(imm32 as i32) as u64
+}
+
+fn mulh(a: u64, b: u64) -> u64 {
+    ((u128::from(a) * u128::from(b)) >> 64) as u64
+}
+
+fn smulh(a: u64, b: u64) -> u64 {
+    ((i128::from(a as i64) * i128::from(b as i64)) >> 64) as u64
+}
+
+/// Computes `2^x / divisor` for the largest `x` that keeps the result within 64 bits, as used by IMUL_RCP.
+fn reciprocal(divisor: u32) -> u64 {
+    let divisor = u64::from(divisor);
+    let p2exp63 = 1u64 << 63;
+    let mut quotient = p2exp63 / divisor;
+    let mut remainder = p2exp63 % divisor;
+    let bsr = 64 - divisor.leading_zeros();
+    for _ in 0..bsr {
+        if remainder >= divisor - remainder {
+            quotient = quotient.wrapping_mul(2).wrapping_add(1);
+            remainder = remainder.wrapping_mul(2).wrapping_sub(divisor);
+        } else {
+            quotient = quotient.wrapping_mul(2);
+            remainder = remainder.wrapping_mul(2);
+        }
+    }
+    quotient
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "ffi")]
+    use crate::{RandomXCache, RandomXVM};
+
+    #[test]
+    fn pure_reciprocal() {
+        // values from the RandomX test suite
+        let vectors = [
+            (3, 12297829382473034410),
+            (13, 11351842506898185609),
+            (33, 17887751829051686415),
+            (65537, 18446462603027742720),
+            (15000001, 10316166306300415204),
+            (3845182035, 10302264209224146340),
+            (0xffffffff, 9223372039002259456),
+        ];
+        for (divisor, expected) in vectors {
+            assert_eq!(reciprocal(divisor), expected);
+        }
+    }
+
+    #[test]
+    fn pure_rejects_empty_key_and_input() {
+        assert!(matches!(
+            PureRandomXCache::new(b""),
+            Err(RandomXError::ParameterError(_))
+        ));
+        let vm = PureRandomXVM::new(PureRandomXCache::new(b"test key 000").unwrap());
+        assert!(matches!(vm.calculate_hash(b""), Err(RandomXError::ParameterError(_))));
+    }
+
+    #[test]
+    fn pure_test_vectors_light_mode() {
+        // same vectors as `test_vectors_light_mode`
+        let mut vm = PureRandomXVM::new(PureRandomXCache::new(b"test key 000").unwrap());
+        let vectors = [
+            (
+                b"This is a test".as_slice(),
+                "639183aae1bf4c9a35884cb46b09cad9175f04efd7684e7262a0ac1c2f0b4e3f",
+            ),
+            (
+                b"Lorem ipsum dolor sit amet".as_slice(),
+                "300a0adb47603dedb42228ccb2b211104f4da45af709cd7547cd049e9489c969",
+            ),
+            (
+                b"sed do eiusmod tempor incididunt ut labore et dolore magna aliqua".as_slice(),
+                "c36d4ed4191e617309867ed66a443be4075014e2b061bcdaf9ce7b721d2b77a8",
+            ),
+        ];
+        for (input, expected) in vectors {
+            assert_eq!(vm.calculate_hash(input).unwrap().to_hex(), expected);
+        }
+
+        vm.reinit_cache(PureRandomXCache::new(b"test key 001").unwrap());
+        let hash = vm
+            .calculate_hash(b"sed do eiusmod tempor incididunt ut labore et dolore magna aliqua")
+            .unwrap();
+        assert_eq!(
+            hash.to_hex(),
+            "e9ff4503201c0c2cca26d285c93ae883f9b1d30c9eb240b820756f2d5a7905fc"
+        );
+    }
+
+    #[cfg(feature = "ffi")]
+    #[test]
+    fn pure_cache_export_and_import() {
+        let key = b"pure-rust key";
+        let flags = RandomXFlag::get_recommended_flags();
+        let cache = RandomXCache::new(flags, key).unwrap();
+        assert_eq!(cache.key(), key);
+        assert!(cache == RandomXCache::new(flags, key).unwrap());
+
+        // Importing what the C library exported gives the same hashes without running Argon2d
+        let imported = PureRandomXCache::import(key, &cache.export().unwrap()).unwrap();
+        assert_eq!(imported.key(), key);
+        let vm = RandomXVM::new(flags, Some(cache), None).unwrap();
+        assert_eq!(
+            PureRandomXVM::new(imported.clone()).calculate_hash(b"a").unwrap(),
+            vm.calculate_hash(b"a").unwrap()
+        );
+
+        let exported = imported.export().unwrap();
+        assert!(PureRandomXCache::import(key, &exported).unwrap() == imported);
+        assert!(matches!(
+            PureRandomXCache::import(b"other key", &exported),
+            Err(RandomXError::CacheExportMismatch(_))
+        ));
+    }
+
+    #[cfg(feature = "ffi")]
+    #[test]
+    fn pure_matches_c_light_vm() {
+        let key = b"pure-rust key";
+        let flags = RandomXFlag::get_recommended_flags();
+        let vm = RandomXVM::new(flags, Some(RandomXCache::new(flags, key).unwrap()), None).unwrap();
+        let pure_vm = PureRandomXVM::new(PureRandomXCache::new(key).unwrap());
+        for input in [&b"a"[..], &[0xff; 76][..], &[0u8; 200][..]] {
+            assert_eq!(
+                pure_vm.calculate_hash(input).unwrap(),
+                vm.calculate_hash(input).unwrap()
+            );
+        }
+    }
+}
@@ -0,0 +1,651 @@
+// Copyright 2026. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! SuperscalarHash: random programs, generated by simulating a superscalar x86 pipeline, that are used to compute
+//! dataset items from the cache.
+
+use super::{blake2_generator::Blake2Generator, reciprocal};
+
+const SUPERSCALAR_LATENCY: usize = 170;
+const CYCLE_MAP_SIZE: usize = SUPERSCALAR_LATENCY + 4;
+const MAX_PROGRAM_SIZE: usize = 3 * SUPERSCALAR_LATENCY + 2;
+const LOOK_FORWARD_CYCLES: usize = 4;
+const MAX_THROWAWAY_COUNT: usize = 256;
+/// `lea` cannot encode r5 as a base register without a displacement, so IADD_RS never targets it.
+const REGISTER_NEEDS_DISPLACEMENT: usize = 5;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Kind {
+    ISubR,
+    IXorR,
+    IAddRs,
+    IMulR,
+    IRorC,
+    IAddC7,
+    IXorC7,
+    IAddC8,
+    IXorC8,
+    IAddC9,
+    IXorC9,
+    IMulhR,
+    ISmulhR,
+    IMulRcp,
+    Invalid,
+}
+
+impl Kind {
+    fn is_multiplication(self) -> bool {
+        matches!(self, Kind::IMulR | Kind::IMulhR | Kind::ISmulhR | Kind::IMulRcp)
+    }
+}
+
+// Execution ports as a bitmask.
+const P0: u8 = 1;
+const P1: u8 = 2;
+const P5: u8 = 4;
+const P01: u8 = P0 | P1;
+const P05: u8 = P0 | P5;
+const P015: u8 = P0 | P1 | P5;
+
+#[derive(Clone, Copy)]
+struct MacroOp {
+    latency: usize,
+    uop1: u8,
+    uop2: u8,
+    dependent: bool,
+}
+
+impl MacroOp {
+    const fn new(latency: usize, uop1: u8, uop2: u8) -> Self {
+        Self {
+            latency,
+            uop1,
+            uop2,
+            dependent: false,
+        }
+    }
+
+    const fn dependent(self) -> Self {
+        Self {
+            dependent: true,
+            ..self
+        }
+    }
+
+    fn is_eliminated(&self) -> bool {
+        self.uop1 == 0
+    }
+
+    fn is_simple(&self) -> bool {
+        self.uop2 == 0
+    }
+}
+
+const ADD_RR: MacroOp = MacroOp::new(1, P015, 0);
+const SUB_RR: MacroOp = ADD_RR;
+const XOR_RR: MacroOp = ADD_RR;
+const MUL_R: MacroOp = MacroOp::new(4, P1, P5);
+const IMUL_R: MacroOp = MUL_R;
+const MOV_RR: MacroOp = MacroOp::new(0, 0, 0);
+const LEA_SIB: MacroOp = MacroOp::new(1, P01, 0);
+const IMUL_RR: MacroOp = MacroOp::new(3, P1, 0);
+const ROR_RI: MacroOp = MacroOp::new(1, P05, 0);
+const ADD_RI: MacroOp = ADD_RR;
+const XOR_RI: MacroOp = ADD_RR;
+const MOV_RI64: MacroOp = ADD_RR;
+
+struct InstructionInfo {
+    kind: Kind,
+    ops: &'static [MacroOp],
+    result_op: usize,
+    dst_op: usize,
+    /// The macro-op at which the source register is selected, if the instruction has one.
+    src_op: Option<usize>,
+}
+
+const fn simple(kind: Kind, op: &'static [MacroOp], src_op: Option<usize>) -> InstructionInfo {
+    InstructionInfo {
+        kind,
+        ops: op,
+        result_op: 0,
+        dst_op: 0,
+        src_op,
+    }
+}
+
+const ISUB_R: InstructionInfo = simple(Kind::ISubR, &[SUB_RR], Some(0));
+const IXOR_R: InstructionInfo = simple(Kind::IXorR, &[XOR_RR], Some(0));
+const IADD_RS: InstructionInfo = simple(Kind::IAddRs, &[LEA_SIB], Some(0));
+const IMUL_R_INFO: InstructionInfo = simple(Kind::IMulR, &[IMUL_RR], Some(0));
+const IROR_C: InstructionInfo = simple(Kind::IRorC, &[ROR_RI], None);
+const IADD_C7: InstructionInfo = simple(Kind::IAddC7, &[ADD_RI], None);
+const IXOR_C7: InstructionInfo = simple(Kind::IXorC7, &[XOR_RI], None);
+const IADD_C8: InstructionInfo = simple(Kind::IAddC8, &[ADD_RI], None);
+const IXOR_C8: InstructionInfo = simple(Kind::IXorC8, &[XOR_RI], None);
+const IADD_C9: InstructionInfo = simple(Kind::IAddC9, &[ADD_RI], None);
+const IXOR_C9: InstructionInfo = simple(Kind::IXorC9, &[XOR_RI], None);
+const IMULH_R: InstructionInfo = InstructionInfo {
+    kind: Kind::IMulhR,
+    ops: &[MOV_RR, MUL_R, MOV_RR],
+    result_op: 1,
+    dst_op: 0,
+    src_op: Some(1),
+};
+const ISMULH_R: InstructionInfo = InstructionInfo {
+    kind: Kind::ISmulhR,
+    ops: &[MOV_RR, IMUL_R, MOV_RR],
+    result_op: 1,
+    dst_op: 0,
+    src_op: Some(1),
+};
+const IMUL_RCP: InstructionInfo = InstructionInfo {
+    kind: Kind::IMulRcp,
+    ops: &[MOV_RI64, IMUL_RR.dependent()],
+    result_op: 1,
+    dst_op: 1,
+    src_op: None,
+};
+const NOP: InstructionInfo = InstructionInfo {
+    kind: Kind::Invalid,
+    ops: &[],
+    result_op: 0,
+    dst_op: 0,
+    src_op: None,
+};
+
+const SLOT_3: [&InstructionInfo; 2] = [&ISUB_R, &IXOR_R];
+const SLOT_3L: [&InstructionInfo; 4] = [&ISUB_R, &IXOR_R, &IMULH_R, &ISMULH_R];
+const SLOT_4: [&InstructionInfo; 2] = [&IROR_C, &IADD_RS];
+const SLOT_7: [&InstructionInfo; 2] = [&IXOR_C7, &IADD_C7];
+const SLOT_8: [&InstructionInfo; 2] = [&IXOR_C8, &IADD_C8];
+const SLOT_9: [&InstructionInfo; 2] = [&IXOR_C9, &IADD_C9];
+
+/// A 16-byte decoder fetch configuration, as the sizes of the instruction slots it holds.
+struct DecoderBuffer {
+    index: usize,
+    counts: &'static [usize],
+}
+
+const BUFFER_484: DecoderBuffer = DecoderBuffer {
+    index: 0,
+    counts: &[4, 8, 4],
+};
+const BUFFER_7333: DecoderBuffer = DecoderBuffer {
+    index: 1,
+    counts: &[7, 3, 3, 3],
+};
+const BUFFER_3733: DecoderBuffer = DecoderBuffer {
+    index: 2,
+    counts: &[3, 7, 3, 3],
+};
+const BUFFER_493: DecoderBuffer = DecoderBuffer {
+    index: 3,
+    counts: &[4, 9, 3],
+};
+const BUFFER_4444: DecoderBuffer = DecoderBuffer {
+    index: 4,
+    counts: &[4, 4, 4, 4],
+};
+const BUFFER_3310: DecoderBuffer = DecoderBuffer {
+    index: 5,
+    counts: &[3, 3, 10],
+};
+const BUFFERS: [&DecoderBuffer; 4] = [&BUFFER_484, &BUFFER_7333, &BUFFER_3733, &BUFFER_493];
+
+fn fetch_next(last: Kind, cycle: usize, mul_count: usize, gen: &mut Blake2Generator) -> &'static DecoderBuffer {
+    // A 128-bit multiplication decodes to two uops, so the following fetch must be 3-3-10
+    if last == Kind::IMulhR || last == Kind::ISmulhR {
+        return &BUFFER_3310;
+    }
+    // Keep the multiplication port saturated
+    if mul_count < cycle + 1 {
+        return &BUFFER_4444;
+    }
+    // IMUL_RCP needs the next buffer to start with a 4-byte slot for its multiplication
+    if last == Kind::IMulRcp {
+        return if gen.get_byte() & 1 == 1 {
+            &BUFFER_484
+        } else {
+            &BUFFER_493
+        };
+    }
+    BUFFERS[(gen.get_byte() & 3) as usize]
+}
+
+#[derive(Clone, Copy)]
+struct RegisterInfo {
+    latency: usize,
+    last_op_group: Kind,
+    last_op_par: i32,
+}
+
+impl Default for RegisterInfo {
+    fn default() -> Self {
+        Self {
+            latency: 0,
+            last_op_group: Kind::Invalid,
+            last_op_par: -1,
+        }
+    }
+}
+
+fn select_register(available: &[usize], gen: &mut Blake2Generator) -> Option<usize> {
+    match available.len() {
+        0 => None,
+        1 => Some(available[0]),
+        n => Some(available[gen.get_u32() as usize % n]),
+    }
+}
+
+/// The instruction currently being scheduled.
+struct Candidate {
+    info: &'static InstructionInfo,
+    src: Option<usize>,
+    dst: usize,
+    modifier: u8,
+    imm32: u32,
+    op_group: Kind,
+    op_group_par: i32,
+    can_reuse: bool,
+    group_par_is_source: bool,
+}
+
+impl Candidate {
+    fn null() -> Self {
+        Self {
+            info: &NOP,
+            src: None,
+            dst: 0,
+            modifier: 0,
+            imm32: 0,
+            op_group: Kind::Invalid,
+            op_group_par: 0,
+            can_reuse: false,
+            group_par_is_source: false,
+        }
+    }
+
+    fn create_for_slot(&mut self, gen: &mut Blake2Generator, slot_size: usize, fetch_type: usize, is_last: bool) {
+        let info = match slot_size {
+            // only the last slot can fit the 3-uop high multiplications
+            3 if is_last => SLOT_3L[(gen.get_byte() & 3) as usize],
+            3 => SLOT_3[(gen.get_byte() & 1) as usize],
+            // the 4-4-4-4 buffer issues multiplications in its first three slots
+            4 if fetch_type == 4 && !is_last => &IMUL_R_INFO,
+            4 => SLOT_4[(gen.get_byte() & 1) as usize],
+            7 => SLOT_7[(gen.get_byte() & 1) as usize],
+            8 => SLOT_8[(gen.get_byte() & 1) as usize],
+            9 => SLOT_9[(gen.get_byte() & 1) as usize],
+            10 => &IMUL_RCP,
+            _ => unreachable!("no decoder buffer has a {} byte slot", slot_size),
+        };
+        self.create(info, gen);
+    }
+
+    fn create(&mut self, info: &'static InstructionInfo, gen: &mut Blake2Generator) {
+        self.info = info;
+        self.src = None;
+        self.dst = 0;
+        self.can_reuse = false;
+        self.group_par_is_source = false;
+        self.modifier = 0;
+        self.imm32 = 0;
+        match info.kind {
+            Kind::ISubR => {
+                self.op_group = Kind::IAddRs;
+                self.group_par_is_source = true;
+            },
+            Kind::IXorR | Kind::IAddRs | Kind::IMulR => {
+                if info.kind == Kind::IAddRs {
+                    self.modifier = gen.get_byte();
+                }
+                self.op_group = info.kind;
+                self.group_par_is_source = true;
+            },
+            Kind::IRorC => {
+                while self.imm32 == 0 {
+                    self.imm32 = u32::from(gen.get_byte() & 63);
+                }
+                self.op_group = Kind::IRorC;
+                self.op_group_par = -1;
+            },
+            Kind::IAddC7 | Kind::IAddC8 | Kind::IAddC9 => {
+                self.imm32 = gen.get_u32();
+                self.op_group = Kind::IAddC7;
+                self.op_group_par = -1;
+            },
+            Kind::IXorC7 | Kind::IXorC8 | Kind::IXorC9 => {
+                self.imm32 = gen.get_u32();
+                self.op_group = Kind::IXorC7;
+                self.op_group_par = -1;
+            },
+            Kind::IMulhR | Kind::ISmulhR => {
+                self.can_reuse = true;
+                self.op_group = info.kind;
+                self.op_group_par = gen.get_u32() as i32;
+            },
+            Kind::IMulRcp => {
+                self.imm32 = gen.get_u32();
+                while self.imm32 & self.imm32.wrapping_sub(1) == 0 {
+                    self.imm32 = gen.get_u32();
+                }
+                self.op_group = Kind::IMulRcp;
+                self.op_group_par = -1;
+            },
+            Kind::Invalid => {},
+        }
+    }
+
+    fn select_destination(
+        &mut self,
+        cycle: usize,
+        allow_chained_mul: bool,
+        registers: &[RegisterInfo; 8],
+        gen: &mut Blake2Generator,
+    ) -> bool {
+        let available = (0..8)
+            .filter(|&i| {
+                let reg = &registers[i];
+                reg.latency <= cycle &&
+                    (self.can_reuse || Some(i) != self.src) &&
+                    (allow_chained_mul || self.op_group != Kind::IMulR || reg.last_op_group != Kind::IMulR) &&
+                    (reg.last_op_group != self.op_group || reg.last_op_par != self.op_group_par) &&
+                    (self.info.kind != Kind::IAddRs || i != REGISTER_NEEDS_DISPLACEMENT)
+            })
+            .collect::<Vec<_>>();
+        match select_register(&available, gen) {
+            Some(dst) => {
+                self.dst = dst;
+                true
+            },
+            None => false,
+        }
+    }
+
+    fn select_source(&mut self, cycle: usize, registers: &[RegisterInfo; 8], gen: &mut Blake2Generator) -> bool {
+        let available = (0..8).filter(|&i| registers[i].latency <= cycle).collect::<Vec<_>>();
+        // With only two candidates for IADD_RS, r5 must be the source because it cannot be the destination
+        if available.len() == 2 && self.info.kind == Kind::IAddRs && available.contains(&REGISTER_NEEDS_DISPLACEMENT) {
+            self.src = Some(REGISTER_NEEDS_DISPLACEMENT);
+            self.op_group_par = REGISTER_NEEDS_DISPLACEMENT as i32;
+            return true;
+        }
+        match select_register(&available, gen) {
+            Some(src) => {
+                self.src = Some(src);
+                if self.group_par_is_source {
+                    self.op_group_par = src as i32;
+                }
+                true
+            },
+            None => false,
+        }
+    }
+
+    fn to_instruction(&self) -> Instruction {
+        Instruction {
+            kind: self.info.kind,
+            dst: self.dst,
+            src: self.src.unwrap_or(self.dst),
+            modifier: self.modifier,
+            imm32: self.imm32,
+            reciprocal: if self.info.kind == Kind::IMulRcp {
+                reciprocal(self.imm32)
+            } else {
+                0
+            },
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Instruction {
+    kind: Kind,
+    dst: usize,
+    src: usize,
+    modifier: u8,
+    imm32: u32,
+    reciprocal: u64,
+}
+
+/// Waits up to `LOOK_FORWARD_CYCLES` cycles for `select` to find a register that is ready.
+fn look_forward(schedule_cycle: &mut usize, cycle: &mut usize, mut select: impl FnMut(usize) -> bool) -> bool {
+    for _ in 0..LOOK_FORWARD_CYCLES {
+        if select(*schedule_cycle) {
+            return true;
+        }
+        *schedule_cycle += 1;
+        *cycle += 1;
+    }
+    false
+}
+
+/// Returns the register with the longest dependency chain, assuming unit latency and unlimited parallelism.
+fn address_register(instructions: &[Instruction]) -> usize {
+    let mut asic_latencies = [0usize; 8];
+    for instr in instructions {
+        let lat_dst = asic_latencies[instr.dst] + 1;
+        let lat_src = if instr.dst == instr.src {
+            0
+        } else {
+            asic_latencies[instr.src] + 1
+        };
+        asic_latencies[instr.dst] = lat_dst.max(lat_src);
+    }
+    let mut address_register = 0;
+    for (i, latency) in asic_latencies.iter().enumerate() {
+        if *latency > asic_latencies[address_register] {
+            address_register = i;
+        }
+    }
+    address_register
+}
+
+type PortBusy = [[bool; 3]; CYCLE_MAP_SIZE];
+
+fn schedule_uop(uop: u8, ports: &mut PortBusy, mut cycle: usize, commit: bool) -> Option<usize> {
+    // Ports are tried in the order P5 -> P0 -> P1 to keep P1 free for multiplications
+    while cycle < CYCLE_MAP_SIZE {
+        for (mask, port) in &[(P5, 2), (P0, 0), (P1, 1)] {
+            if uop & mask != 0 && !ports[cycle][*port] {
+                if commit {
+                    ports[cycle][*port] = true;
+                }
+                return Some(cycle);
+            }
+        }
+        cycle += 1;
+    }
+    None
+}
+
+fn schedule_mop(
+    mop: &MacroOp,
+    ports: &mut PortBusy,
+    mut cycle: usize,
+    dep_cycle: usize,
+    commit: bool,
+) -> Option<usize> {
+    // the second half of IMUL_RCP depends on the first
+    if mop.dependent {
+        cycle = cycle.max(dep_cycle);
+    }
+    if mop.is_eliminated() {
+        return Some(cycle);
+    }
+    if mop.is_simple() {
+        return schedule_uop(mop.uop1, ports, cycle, commit);
+    }
+    // both uops of a two-uop macro-op have to execute in the same cycle
+    while cycle < CYCLE_MAP_SIZE {
+        let cycle1 = schedule_uop(mop.uop1, ports, cycle, false);
+        let cycle2 = schedule_uop(mop.uop2, ports, cycle, false);
+        if cycle1.is_some() && cycle1 == cycle2 {
+            if commit {
+                schedule_uop(mop.uop1, ports, cycle, true);
+                schedule_uop(mop.uop2, ports, cycle, true);
+            }
+            return cycle1;
+        }
+        cycle += 1;
+    }
+    None
+}
+
+/// A generated SuperscalarHash program together with the register used to pick the next cache block.
+pub(crate) struct SuperscalarProgram {
+    instructions: Vec<Instruction>,
+    address_register: usize,
+}
+
+impl SuperscalarProgram {
+    pub fn generate(gen: &mut Blake2Generator) -> Self {
+        let mut ports: PortBusy = [[false; 3]; CYCLE_MAP_SIZE];
+        let mut registers = [RegisterInfo::default(); 8];
+        let mut instructions = Vec::with_capacity(MAX_PROGRAM_SIZE);
+
+        let mut current = Candidate::null();
+        let mut macro_op_index = 0;
+        let mut cycle = 0;
+        let mut dep_cycle = 0;
+        let mut ports_saturated = false;
+        let mut mul_count = 0;
+        let mut throw_away_count = 0;
+
+        let mut decode_cycle = 0;
+        while decode_cycle < SUPERSCALAR_LATENCY && !ports_saturated && instructions.len() < MAX_PROGRAM_SIZE {
+            let buffer = fetch_next(current.info.kind, decode_cycle, mul_count, gen);
+            let mut buffer_index = 0;
+
+            while buffer_index < buffer.counts.len() {
+                let top_cycle = cycle;
+
+                if macro_op_index >= current.info.ops.len() {
+                    if ports_saturated || instructions.len() >= MAX_PROGRAM_SIZE {
+                        break;
+                    }
+                    current.create_for_slot(
+                        gen,
+                        buffer.counts[buffer_index],
+                        buffer.index,
+                        buffer.counts.len() == buffer_index + 1,
+                    );
+                    macro_op_index = 0;
+                }
+                let mop = current.info.ops[macro_op_index];
+
+                let mut schedule_cycle = match schedule_mop(&mop, &mut ports, cycle, dep_cycle, false) {
+                    Some(c) => c,
+                    None => {
+                        ports_saturated = true;
+                        break;
+                    },
+                };
+
+                let allow_chained_mul = throw_away_count > 0;
+                let found = (current.info.src_op != Some(macro_op_index) ||
+                    look_forward(&mut schedule_cycle, &mut cycle, |c| {
+                        current.select_source(c, &registers, gen)
+                    })) &&
+                    (current.info.dst_op != macro_op_index ||
+                        look_forward(&mut schedule_cycle, &mut cycle, |c| {
+                            current.select_destination(c, allow_chained_mul, &registers, gen)
+                        }));
+                if !found {
+                    // throw the instruction away and try another one, or give up on this decode buffer
+                    if throw_away_count < MAX_THROWAWAY_COUNT {
+                        throw_away_count += 1;
+                        macro_op_index = current.info.ops.len();
+                        continue;
+                    }
+                    current = Candidate::null();
+                    break;
+                }
+                throw_away_count = 0;
+
+                schedule_cycle = match schedule_mop(&mop, &mut ports, schedule_cycle, schedule_cycle, true) {
+                    Some(c) => c,
+                    None => {
+                        ports_saturated = true;
+                        break;
+                    },
+                };
+                dep_cycle = schedule_cycle + mop.latency;
+
+                if macro_op_index == current.info.result_op {
+                    let reg = &mut registers[current.dst];
+                    reg.latency = dep_cycle;
+                    reg.last_op_group = current.op_group;
+                    reg.last_op_par = current.op_group_par;
+                }
+                buffer_index += 1;
+                macro_op_index += 1;
+
+                if schedule_cycle >= SUPERSCALAR_LATENCY {
+                    ports_saturated = true;
+                }
+                cycle = top_cycle;
+
+                if macro_op_index >= current.info.ops.len() {
+                    instructions.push(current.to_instruction());
+                    if current.info.kind.is_multiplication() {
+                        mul_count += 1;
+                    }
+                }
+            }
+            cycle += 1;
+            decode_cycle += 1;
+        }
+
+        let address_register = address_register(&instructions);
+
+        Self {
+            instructions,
+            address_register,
+        }
+    }
+
+    pub fn address_register(&self) -> usize {
+        self.address_register
+    }
+
+    pub fn execute(&self, r: &mut [u64; 8]) {
+        for instr in &self.instructions {
+            let src = r[instr.src];
+            let dst = &mut r[instr.dst];
+            let imm = super::sign_extend(instr.imm32);
+            match instr.kind {
+                Kind::ISubR => *dst = dst.wrapping_sub(src),
+                Kind::IXorR => *dst ^= src,
+                Kind::IAddRs => *dst = dst.wrapping_add(src << ((instr.modifier >> 2) % 4)),
+                Kind::IMulR => *dst = dst.wrapping_mul(src),
+                Kind::IRorC => *dst = dst.rotate_right(instr.imm32),
+                Kind::IAddC7 | Kind::IAddC8 | Kind::IAddC9 => *dst = dst.wrapping_add(imm),
+                Kind::IXorC7 | Kind::IXorC8 | Kind::IXorC9 => *dst ^= imm,
+                Kind::IMulhR => *dst = super::mulh(*dst, src),
+                Kind::ISmulhR => *dst = super::smulh(*dst, src),
+                Kind::IMulRcp => *dst = dst.wrapping_mul(instr.reciprocal),
+                Kind::Invalid => {},
+            }
+        }
+    }
+}
@@ -0,0 +1,678 @@
+// Copyright 2026. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! The RandomX virtual machine: program generation from the AES-4R generator, compilation to a register-indexed
+//! bytecode and interpretation against the scratchpad.
+
+use super::{aes, cache::PureRandomXCacheInner, mulh, reciprocal, sign_extend, smulh};
+
+const PROGRAM_SIZE: usize = 256;
+const PROGRAM_ITERATIONS: usize = 2048;
+const SCRATCHPAD_L1: u64 = 16 * 1024;
+const SCRATCHPAD_L2: u64 = 256 * 1024;
+pub(crate) const SCRATCHPAD_L3: usize = 2 * 1024 * 1024;
+const SCRATCHPAD_L1_MASK: u64 = (SCRATCHPAD_L1 - 1) & !7;
+const SCRATCHPAD_L2_MASK: u64 = (SCRATCHPAD_L2 - 1) & !7;
+const SCRATCHPAD_L3_MASK: u64 = (SCRATCHPAD_L3 as u64 - 1) & !7;
+const SCRATCHPAD_L3_MASK64: u64 = (SCRATCHPAD_L3 as u64 - 1) & !63;
+const CACHE_LINE_SIZE: u64 = 64;
+const DATASET_BASE_SIZE: u64 = 2_147_483_648;
+const DATASET_EXTRA_ITEMS: u64 = 33_554_368 / CACHE_LINE_SIZE;
+const CACHE_LINE_ALIGN_MASK: u32 = ((DATASET_BASE_SIZE - 1) & !(CACHE_LINE_SIZE - 1)) as u32;
+const CONDITION_OFFSET: u32 = 8;
+const CONDITION_MASK: u64 = (1 << 8) - 1;
+const STORE_L3_CONDITION: u8 = 14;
+const MANTISSA_SIZE: u32 = 52;
+const MANTISSA_MASK: u64 = (1 << MANTISSA_SIZE) - 1;
+const EXPONENT_MASK: u64 = (1 << 11) - 1;
+const EXPONENT_BIAS: u64 = 1023;
+const DYNAMIC_EXPONENT_BITS: u32 = 4;
+const STATIC_EXPONENT_BITS: u32 = 4;
+const CONST_EXPONENT_BITS: u64 = 0x300;
+const DYNAMIC_MANTISSA_MASK: u64 = (1 << (MANTISSA_SIZE + DYNAMIC_EXPONENT_BITS)) - 1;
+const FSCAL_MASK: u64 = 0x80F0_0000_0000_0000;
+
+/// Instruction opcodes in frequency-table order, with how many of the 256 opcode values map to each.
+const FREQUENCIES: [(Op, u8); 29] = [
+    (Op::IAddRs, 16),
+    (Op::IAddM, 7),
+    (Op::ISubR, 16),
+    (Op::ISubM, 7),
+    (Op::IMulR, 16),
+    (Op::IMulM, 4),
+    (Op::IMulhR, 4),
+    (Op::IMulhM, 1),
+    (Op::ISmulhR, 4),
+    (Op::ISmulhM, 1),
+    (Op::IMulRcp, 8),
+    (Op::INegR, 2),
+    (Op::IXorR, 15),
+    (Op::IXorM, 5),
+    (Op::IRorR, 8),
+    (Op::IRolR, 2),
+    (Op::ISwapR, 4),
+    (Op::FSwapR, 4),
+    (Op::FAddR, 16),
+    (Op::FAddM, 5),
+    (Op::FSubR, 16),
+    (Op::FSubM, 5),
+    (Op::FScalR, 6),
+    (Op::FMulR, 32),
+    (Op::FDivM, 4),
+    (Op::FSqrtR, 6),
+    (Op::CBranch, 25),
+    (Op::CFround, 1),
+    (Op::IStore, 16),
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Op {
+    IAddRs,
+    IAddM,
+    ISubR,
+    ISubM,
+    IMulR,
+    IMulM,
+    IMulhR,
+    IMulhM,
+    ISmulhR,
+    ISmulhM,
+    IMulRcp,
+    INegR,
+    IXorR,
+    IXorM,
+    IRorR,
+    IRolR,
+    ISwapR,
+    FSwapR,
+    FAddR,
+    FAddM,
+    FSubR,
+    FSubM,
+    FScalR,
+    FMulR,
+    FDivM,
+    FSqrtR,
+    CBranch,
+    CFround,
+    IStore,
+    Nop,
+}
+
+fn opcode_table() -> [Op; 256] {
+    let mut table = [Op::Nop; 256];
+    let mut i = 0;
+    for (op, count) in &FREQUENCIES {
+        for _ in 0..*count {
+            table[i] = *op;
+            i += 1;
+        }
+    }
+    table
+}
+
+/// A decoded instruction. `src` is `None` when the instruction uses `imm` (or zero, for memory operands) in place of a
+/// source register.
+#[derive(Clone, Copy, Debug)]
+struct Bytecode {
+    op: Op,
+    dst: usize,
+    src: Option<usize>,
+    imm: u64,
+    mask: u64,
+    shift: u32,
+    target: isize,
+}
+
+impl Bytecode {
+    fn source(&self, r: &[u64; 8]) -> u64 {
+        self.src.map_or(self.imm, |src| r[src])
+    }
+
+    fn source_index(&self) -> usize {
+        self.src.unwrap_or(self.dst)
+    }
+
+    fn address(&self, r: &[u64; 8]) -> usize {
+        (self.src.map_or(0, |src| r[src]).wrapping_add(self.imm) & self.mask) as usize
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Rounding {
+    Nearest,
+    Down,
+    Up,
+    Zero,
+}
+
+impl Rounding {
+    fn from_mode(mode: u64) -> Self {
+        match mode & 3 {
+            0 => Rounding::Nearest,
+            1 => Rounding::Down,
+            2 => Rounding::Up,
+            _ => Rounding::Zero,
+        }
+    }
+}
+
+fn next_up(x: f64) -> f64 {
+    if x.is_nan() || x == f64::INFINITY {
+        x
+    } else if x == 0.0 {
+        f64::from_bits(1)
+    } else if x > 0.0 {
+        f64::from_bits(x.to_bits() + 1)
+    } else {
+        f64::from_bits(x.to_bits() - 1)
+    }
+}
+
+fn next_down(x: f64) -> f64 {
+    -next_up(-x)
+}
+
+/// Turns the round-to-nearest `result` into the result under `mode`, given the sign of the rounding error
+/// (exact value minus `result`).
+fn directed(mode: Rounding, result: f64, error: f64) -> f64 {
+    match mode {
+        Rounding::Down if error < 0.0 => next_down(result),
+        Rounding::Up if error > 0.0 => next_up(result),
+        Rounding::Zero if result > 0.0 && error < 0.0 => next_down(result),
+        Rounding::Zero if result < 0.0 && error > 0.0 => next_up(result),
+        _ => result,
+    }
+}
+
+/// The result of a finite operation that overflowed to infinity under round-to-nearest.
+fn overflow(mode: Rounding, result: f64) -> f64 {
+    match mode {
+        Rounding::Down if result > 0.0 => f64::MAX,
+        Rounding::Up if result < 0.0 => f64::MIN,
+        Rounding::Zero => f64::MAX.copysign(result),
+        _ => result,
+    }
+}
+
+// Rust only exposes round-to-nearest arithmetic, so the other modes are emulated from the exact rounding error.
+fn add(mode: Rounding, a: f64, b: f64) -> f64 {
+    let sum = a + b;
+    if mode == Rounding::Nearest {
+        return sum;
+    }
+    if sum.is_infinite() && a.is_finite() && b.is_finite() {
+        return overflow(mode, sum);
+    }
+    if sum == 0.0 {
+        // an exact zero sum is negative when rounding down, unless both operands are positive
+        return if mode == Rounding::Down && (a.is_sign_negative() || b.is_sign_negative()) {
+            -0.0
+        } else {
+            sum
+        };
+    }
+    let bb = sum - a;
+    let error = (a - (sum - bb)) + (b - bb);
+    directed(mode, sum, error)
+}
+
+fn mul(mode: Rounding, a: f64, b: f64) -> f64 {
+    let product = a * b;
+    if mode == Rounding::Nearest || !(a.is_finite() && b.is_finite()) {
+        return product;
+    }
+    if product.is_infinite() {
+        return overflow(mode, product);
+    }
+    directed(mode, product, a.mul_add(b, -product))
+}
+
+fn div(mode: Rounding, a: f64, b: f64) -> f64 {
+    let quotient = a / b;
+    if mode == Rounding::Nearest || !a.is_finite() || b == 0.0 {
+        return quotient;
+    }
+    if quotient.is_infinite() {
+        return overflow(mode, quotient);
+    }
+    let remainder = (-quotient).mul_add(b, a);
+    directed(mode, quotient, if b < 0.0 { -remainder } else { remainder })
+}
+
+fn sqrt(mode: Rounding, a: f64) -> f64 {
+    let root = a.sqrt();
+    if mode == Rounding::Nearest || !root.is_finite() {
+        return root;
+    }
+    directed(mode, root, (-root).mul_add(root, a))
+}
+
+fn read_u64(scratchpad: &[u8], address: usize) -> u64 {
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&scratchpad[address..address + 8]);
+    u64::from_le_bytes(bytes)
+}
+
+fn write_u64(scratchpad: &mut [u8], address: usize, value: u64) {
+    scratchpad[address..address + 8].copy_from_slice(&value.to_le_bytes());
+}
+
+/// Converts the two signed 32-bit integers at `address` to a pair of doubles.
+fn read_f128(scratchpad: &[u8], address: usize) -> [f64; 2] {
+    let lo = read_u64(scratchpad, address);
+    [f64::from(lo as i32), f64::from((lo >> 32) as i32)]
+}
+
+fn small_positive_float_bits(entropy: u64) -> u64 {
+    let exponent = ((entropy >> 59) + EXPONENT_BIAS) & EXPONENT_MASK;
+    (exponent << MANTISSA_SIZE) | (entropy & MANTISSA_MASK)
+}
+
+fn float_mask(entropy: u64) -> u64 {
+    const MASK_22_BIT: u64 = (1 << 22) - 1;
+    let exponent = CONST_EXPONENT_BITS | ((entropy >> (64 - STATIC_EXPONENT_BITS)) << DYNAMIC_EXPONENT_BITS);
+    (entropy & MASK_22_BIT) | (exponent << MANTISSA_SIZE)
+}
+
+/// The register file hashed between programs and at the end of the chain.
+#[derive(Default)]
+struct RegisterFile {
+    r: [u64; 8],
+    f: [[f64; 2]; 4],
+    e: [[f64; 2]; 4],
+    a: [[f64; 2]; 4],
+}
+
+impl RegisterFile {
+    fn to_bytes(&self) -> [u8; 256] {
+        let mut bytes = [0u8; 256];
+        let floats = self.f.iter().chain(self.e.iter()).chain(self.a.iter()).flatten();
+        let words = self.r.iter().copied().chain(floats.map(|x| x.to_bits()));
+        for (chunk, word) in bytes.chunks_exact_mut(8).zip(words) {
+            chunk.copy_from_slice(&word.to_le_bytes());
+        }
+        bytes
+    }
+}
+
+pub(crate) struct Machine<'a> {
+    cache: &'a PureRandomXCacheInner,
+    scratchpad: Vec<u8>,
+    reg: RegisterFile,
+    rounding: Rounding,
+    opcodes: [Op; 256],
+}
+
+impl<'a> Machine<'a> {
+    pub fn new(cache: &'a PureRandomXCacheInner) -> Self {
+        Self {
+            cache,
+            scratchpad: vec![0u8; SCRATCHPAD_L3],
+            reg: RegisterFile::default(),
+            rounding: Rounding::Nearest,
+            opcodes: opcode_table(),
+        }
+    }
+
+    /// Runs the full program chain seeded by the 64-byte Blake2b hash of the input and returns the final register
+    /// file with the scratchpad fingerprint in place of the `a` registers.
+    pub fn run_chain(&mut self, seed: &mut [u8; 64], program_count: usize) -> [u8; 256] {
+        aes::fill_aes_1rx4(seed, &mut self.scratchpad);
+        self.rounding = Rounding::Nearest;
+        for chain in 0..program_count {
+            self.run(seed);
+            if chain + 1 < program_count {
+                seed.copy_from_slice(&super::blake2b_512(&self.reg.to_bytes()));
+            }
+        }
+        let mut bytes = self.reg.to_bytes();
+        bytes[192..].copy_from_slice(&aes::hash_aes_1rx4(&self.scratchpad));
+        bytes
+    }
+
+    fn run(&mut self, seed: &[u8; 64]) {
+        let mut program = [0u8; 128 + 8 * PROGRAM_SIZE];
+        aes::fill_aes_4rx4(seed, &mut program);
+        let entropy = |i: usize| read_u64(&program, 8 * i);
+
+        for (i, a) in self.reg.a.iter_mut().enumerate() {
+            *a = [
+                f64::from_bits(small_positive_float_bits(entropy(2 * i))),
+                f64::from_bits(small_positive_float_bits(entropy(2 * i + 1))),
+            ];
+        }
+        let mut ma = entropy(8) as u32 & CACHE_LINE_ALIGN_MASK;
+        let mut mx = entropy(10) as u32;
+        let address_registers = entropy(12);
+        let read_reg = [
+            (address_registers & 1) as usize,
+            2 + (address_registers >> 1 & 1) as usize,
+            4 + (address_registers >> 2 & 1) as usize,
+            6 + (address_registers >> 3 & 1) as usize,
+        ];
+        let dataset_offset = (entropy(13) % (DATASET_EXTRA_ITEMS + 1)) * CACHE_LINE_SIZE;
+        let e_mask = [float_mask(entropy(14)), float_mask(entropy(15))];
+
+        let bytecode = self.compile(&program[128..]);
+
+        let mut r = [0u64; 8];
+        let mut f = [[0f64; 2]; 4];
+        let mut e = [[0f64; 2]; 4];
+        let a = self.reg.a;
+        let mut sp_addr0 = mx;
+        let mut sp_addr1 = ma;
+        for _ in 0..PROGRAM_ITERATIONS {
+            let sp_mix = r[read_reg[0]] ^ r[read_reg[1]];
+            sp_addr0 = ((u64::from(sp_addr0) ^ sp_mix) & SCRATCHPAD_L3_MASK64) as u32;
+            sp_addr1 = ((u64::from(sp_addr1) ^ (sp_mix >> 32)) & SCRATCHPAD_L3_MASK64) as u32;
+            let (addr0, addr1) = (sp_addr0 as usize, sp_addr1 as usize);
+
+            for (i, reg) in r.iter_mut().enumerate() {
+                *reg ^= read_u64(&self.scratchpad, addr0 + 8 * i);
+            }
+            for (i, reg) in f.iter_mut().enumerate() {
+                *reg = read_f128(&self.scratchpad, addr1 + 8 * i);
+            }
+            for (i, reg) in e.iter_mut().enumerate() {
+                let value = read_f128(&self.scratchpad, addr1 + 8 * (4 + i));
+                for lane in 0..2 {
+                    reg[lane] = f64::from_bits((value[lane].to_bits() & DYNAMIC_MANTISSA_MASK) | e_mask[lane]);
+                }
+            }
+
+            self.execute(&bytecode, &mut r, &mut f, &mut e, &a, e_mask);
+
+            mx ^= (r[read_reg[2]] ^ r[read_reg[3]]) as u32;
+            mx &= CACHE_LINE_ALIGN_MASK;
+            let item = self
+                .cache
+                .dataset_item((dataset_offset + u64::from(ma)) / CACHE_LINE_SIZE);
+            for (reg, word) in r.iter_mut().zip(item.iter()) {
+                *reg ^= word;
+            }
+            std::mem::swap(&mut mx, &mut ma);
+
+            for (i, reg) in r.iter().enumerate() {
+                write_u64(&mut self.scratchpad, addr1 + 8 * i, *reg);
+            }
+            for (i, (fi, ei)) in f.iter_mut().zip(e.iter()).enumerate() {
+                for lane in 0..2 {
+                    fi[lane] = f64::from_bits(fi[lane].to_bits() ^ ei[lane].to_bits());
+                    write_u64(&mut self.scratchpad, addr0 + 16 * i + 8 * lane, fi[lane].to_bits());
+                }
+            }
+
+            sp_addr0 = 0;
+            sp_addr1 = 0;
+        }
+
+        self.reg.r = r;
+        self.reg.f = f;
+        self.reg.e = e;
+    }
+
+    fn compile(&self, instructions: &[u8]) -> Vec<Bytecode> {
+        let mut register_usage = [-1isize; 8];
+        let mut bytecode = Vec::with_capacity(PROGRAM_SIZE);
+        for (i, raw) in instructions.chunks_exact(8).enumerate() {
+            let op = self.opcodes[raw[0] as usize];
+            let dst = raw[1] as usize % 8;
+            let src = raw[2] as usize % 8;
+            let modifier = raw[3];
+            let imm32 = u32::from_le_bytes([raw[4], raw[5], raw[6], raw[7]]);
+            let mem_mask = if modifier % 4 != 0 {
+                SCRATCHPAD_L1_MASK
+            } else {
+                SCRATCHPAD_L2_MASK
+            };
+            let mut ibc = Bytecode {
+                op,
+                dst,
+                src: Some(src),
+                imm: sign_extend(imm32),
+                mask: mem_mask,
+                shift: 0,
+                target: 0,
+            };
+            match op {
+                Op::IAddRs => {
+                    ibc.shift = u32::from((modifier >> 2) % 4);
+                    if dst != 5 {
+                        ibc.imm = 0;
+                    }
+                    register_usage[dst] = i as isize;
+                },
+                Op::IAddM | Op::ISubM | Op::IMulM | Op::IMulhM | Op::ISmulhM | Op::IXorM => {
+                    if src == dst {
+                        ibc.src = None;
+                        ibc.mask = SCRATCHPAD_L3_MASK;
+                    }
+                    register_usage[dst] = i as isize;
+                },
+                Op::ISubR | Op::IMulR | Op::IXorR => {
+                    if src == dst {
+                        ibc.src = None;
+                    }
+                    register_usage[dst] = i as isize;
+                },
+                Op::IRorR | Op::IRolR => {
+                    if src == dst {
+                        ibc.src = None;
+                        ibc.imm = u64::from(imm32);
+                    }
+                    register_usage[dst] = i as isize;
+                },
+                Op::IMulhR | Op::ISmulhR | Op::INegR => {
+                    register_usage[dst] = i as isize;
+                },
+                Op::IMulRcp => {
+                    if imm32 & imm32.wrapping_sub(1) == 0 {
+                        ibc.op = Op::Nop;
+                    } else {
+                        ibc.op = Op::IMulR;
+                        ibc.src = None;
+                        ibc.imm = reciprocal(imm32);
+                        register_usage[dst] = i as isize;
+                    }
+                },
+                Op::ISwapR => {
+                    if src == dst {
+                        ibc.op = Op::Nop;
+                    } else {
+                        register_usage[dst] = i as isize;
+                        register_usage[src] = i as isize;
+                    }
+                },
+                Op::FAddR | Op::FSubR | Op::FMulR => {
+                    ibc.dst = dst % 4;
+                    ibc.src = Some(src % 4);
+                },
+                Op::FAddM | Op::FSubM | Op::FDivM | Op::FScalR | Op::FSqrtR => {
+                    ibc.dst = dst % 4;
+                },
+                Op::FSwapR => {},
+                Op::CBranch => {
+                    let shift = u32::from(modifier >> 4) + CONDITION_OFFSET;
+                    ibc.target = register_usage[dst];
+                    // clearing the bit below the condition mask limits the number of successive jumps
+                    ibc.imm = (ibc.imm | (1 << shift)) & !(1 << (shift - 1));
+                    ibc.mask = CONDITION_MASK << shift;
+                    register_usage = [i as isize; 8];
+                },
+                Op::CFround => {
+                    ibc.imm = u64::from(imm32 & 63);
+                },
+                Op::IStore => {
+                    if modifier >> 4 >= STORE_L3_CONDITION {
+                        ibc.mask = SCRATCHPAD_L3_MASK;
+                    }
+                },
+                Op::Nop => {},
+            }
+            bytecode.push(ibc);
+        }
+        bytecode
+    }
+
+    fn execute(
+        &mut self,
+        bytecode: &[Bytecode],
+        r: &mut [u64; 8],
+        f: &mut [[f64; 2]; 4],
+        e: &mut [[f64; 2]; 4],
+        a: &[[f64; 2]; 4],
+        e_mask: [u64; 2],
+    ) {
+        let sp = &mut self.scratchpad;
+        let mut pc = 0isize;
+        while pc < bytecode.len() as isize {
+            let ibc = &bytecode[pc as usize];
+            let mode = self.rounding;
+            match ibc.op {
+                Op::IAddRs => {
+                    r[ibc.dst] = r[ibc.dst]
+                        .wrapping_add(ibc.source(r) << ibc.shift)
+                        .wrapping_add(ibc.imm)
+                },
+                Op::IAddM => r[ibc.dst] = r[ibc.dst].wrapping_add(read_u64(sp, ibc.address(r))),
+                Op::ISubR => r[ibc.dst] = r[ibc.dst].wrapping_sub(ibc.source(r)),
+                Op::ISubM => r[ibc.dst] = r[ibc.dst].wrapping_sub(read_u64(sp, ibc.address(r))),
+                Op::IMulR => r[ibc.dst] = r[ibc.dst].wrapping_mul(ibc.source(r)),
+                Op::IMulM => r[ibc.dst] = r[ibc.dst].wrapping_mul(read_u64(sp, ibc.address(r))),
+                Op::IMulhR => r[ibc.dst] = mulh(r[ibc.dst], ibc.source(r)),
+                Op::IMulhM => r[ibc.dst] = mulh(r[ibc.dst], read_u64(sp, ibc.address(r))),
+                Op::ISmulhR => r[ibc.dst] = smulh(r[ibc.dst], ibc.source(r)),
+                Op::ISmulhM => r[ibc.dst] = smulh(r[ibc.dst], read_u64(sp, ibc.address(r))),
+                Op::INegR => r[ibc.dst] = r[ibc.dst].wrapping_neg(),
+                Op::IXorR => r[ibc.dst] ^= ibc.source(r),
+                Op::IXorM => r[ibc.dst] ^= read_u64(sp, ibc.address(r)),
+                Op::IRorR => r[ibc.dst] = r[ibc.dst].rotate_right((ibc.source(r) & 63) as u32),
+                Op::IRolR => r[ibc.dst] = r[ibc.dst].rotate_left((ibc.source(r) & 63) as u32),
+                Op::ISwapR => r.swap(ibc.dst, ibc.source_index()),
+                Op::FSwapR => {
+                    let reg = if ibc.dst < 4 {
+                        &mut f[ibc.dst]
+                    } else {
+                        &mut e[ibc.dst - 4]
+                    };
+                    reg.swap(0, 1);
+                },
+                Op::FAddR => {
+                    let src = a[ibc.source_index()];
+                    for lane in 0..2 {
+                        f[ibc.dst][lane] = add(mode, f[ibc.dst][lane], src[lane]);
+                    }
+                },
+                Op::FAddM => {
+                    let src = read_f128(sp, ibc.address(r));
+                    for lane in 0..2 {
+                        f[ibc.dst][lane] = add(mode, f[ibc.dst][lane], src[lane]);
+                    }
+                },
+                Op::FSubR => {
+                    let src = a[ibc.source_index()];
+                    for lane in 0..2 {
+                        f[ibc.dst][lane] = add(mode, f[ibc.dst][lane], -src[lane]);
+                    }
+                },
+                Op::FSubM => {
+                    let src = read_f128(sp, ibc.address(r));
+                    for lane in 0..2 {
+                        f[ibc.dst][lane] = add(mode, f[ibc.dst][lane], -src[lane]);
+                    }
+                },
+                Op::FScalR => {
+                    for lane in &mut f[ibc.dst] {
+                        *lane = f64::from_bits(lane.to_bits() ^ FSCAL_MASK);
+                    }
+                },
+                Op::FMulR => {
+                    let src = a[ibc.source_index()];
+                    for lane in 0..2 {
+                        e[ibc.dst][lane] = mul(mode, e[ibc.dst][lane], src[lane]);
+                    }
+                },
+                Op::FDivM => {
+                    let src = read_f128(sp, ibc.address(r));
+                    for lane in 0..2 {
+                        let divisor = f64::from_bits((src[lane].to_bits() & DYNAMIC_MANTISSA_MASK) | e_mask[lane]);
+                        e[ibc.dst][lane] = div(mode, e[ibc.dst][lane], divisor);
+                    }
+                },
+                Op::FSqrtR => {
+                    for lane in &mut e[ibc.dst] {
+                        *lane = sqrt(mode, *lane);
+                    }
+                },
+                Op::CBranch => {
+                    r[ibc.dst] = r[ibc.dst].wrapping_add(ibc.imm);
+                    if r[ibc.dst] & ibc.mask == 0 {
+                        pc = ibc.target;
+                    }
+                },
+                Op::CFround => {
+                    self.rounding = Rounding::from_mode(ibc.source(r).rotate_right(ibc.imm as u32));
+                },
+                Op::IStore => {
+                    let address = (r[ibc.dst].wrapping_add(ibc.imm) & ibc.mask) as usize;
+                    write_u64(sp, address, ibc.source(r));
+                },
+                Op::IMulRcp | Op::Nop => {},
+            }
+            pc += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn directed_rounding() {
+        let tiny = 2f64.powi(-60);
+        assert_eq!(add(Rounding::Nearest, 1.0, tiny), 1.0);
+        assert_eq!(add(Rounding::Up, 1.0, tiny), next_up(1.0));
+        assert_eq!(add(Rounding::Down, 1.0, -tiny), next_down(1.0));
+        assert_eq!(add(Rounding::Zero, -1.0, -tiny), -1.0);
+        assert!(add(Rounding::Down, 1.0, -1.0).is_sign_negative());
+        assert!(add(Rounding::Up, 1.0, -1.0).is_sign_positive());
+
+        let third = div(Rounding::Nearest, 1.0, 3.0);
+        assert_eq!(div(Rounding::Up, 1.0, 3.0), next_up(third));
+        assert_eq!(div(Rounding::Down, 1.0, 3.0), third);
+        assert_eq!(div(Rounding::Zero, -1.0, 3.0), -third);
+
+        let root = sqrt(Rounding::Nearest, 2.0);
+        assert_eq!(sqrt(Rounding::Up, 2.0), root);
+        assert_eq!(sqrt(Rounding::Down, 2.0), next_down(root));
+        assert_eq!(sqrt(Rounding::Up, 4.0), 2.0);
+
+        let product = mul(Rounding::Nearest, third, 3.0);
+        assert_eq!(product, 1.0);
+        assert_eq!(mul(Rounding::Down, third, 3.0), next_down(1.0));
+        assert_eq!(mul(Rounding::Up, f64::MAX, 2.0), f64::INFINITY);
+        assert_eq!(mul(Rounding::Zero, f64::MAX, 2.0), f64::MAX);
+        assert_eq!(mul(Rounding::Down, f64::MAX, -2.0), f64::NEG_INFINITY);
+    }
+}
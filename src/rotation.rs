@@ -0,0 +1,313 @@
+// Copyright 2026. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::{
+    mem,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+        Mutex,
+        MutexGuard,
+        RwLock,
+        RwLockReadGuard,
+    },
+    thread::{self, JoinHandle},
+};
+
+use crate::{RandomXCache, RandomXDataset, RandomXError, RandomXFlag, RandomXVM};
+
+type BuildResult = Result<(RandomXCache, RandomXDataset), RandomXError>;
+
+#[derive(Debug)]
+struct KeyState {
+    key: Vec<u8>,
+    cache: RandomXCache,
+    dataset: RandomXDataset,
+    previous: Option<(Vec<u8>, RandomXCache)>,
+}
+
+#[derive(Debug)]
+struct PendingKey {
+    key: Vec<u8>,
+    switch_height: u64,
+    cancel: Arc<AtomicBool>,
+    handle: JoinHandle<BuildResult>,
+}
+
+impl PendingKey {
+    /// Stops the build and waits for its thread to exit, so its dataset memory is released.
+    fn cancel(self) {
+        self.cancel.store(true, Ordering::Relaxed);
+        // The result is discarded, it is `RandomXError::Cancelled` unless the build had already finished
+        let _result = self.handle.join();
+    }
+}
+
+/// Manages the RandomX key of a set of fast-mode VMs across key changes.
+///
+/// The cache and dataset for the upcoming key are built on a background thread while the VMs keep hashing with the
+/// current key. Once the switch height is reached, the new dataset is attached to every VM at once. The cache of the
+/// previous key is kept so that late blocks can still be verified in light mode.
+#[derive(Debug)]
+pub struct KeyRotationManager {
+    flags: RandomXFlag,
+    threads: u32,
+    state: RwLock<KeyState>,
+    vms: Vec<Mutex<RandomXVM>>,
+    pending: Mutex<Option<PendingKey>>,
+}
+
+impl KeyRotationManager {
+    /// Creates a new manager, building the cache and dataset for `key` and `vm_count` fast-mode VMs that use them.
+    ///
+    /// `flags` are the flags used for the cache, dataset and VMs. FLAG_FULL_MEM is added for the VMs.
+    ///
+    /// `threads` is the number of threads used to initialize each dataset, see [`RandomXDataset::new_parallel`].
+    pub fn new(flags: RandomXFlag, key: &[u8], vm_count: usize, threads: u32) -> Result<Self, RandomXError> {
+        let (cache, dataset) = build(flags, key, threads, &AtomicBool::new(false))?;
+        let vms = (0..vm_count)
+            .map(|_| RandomXVM::new(flags | RandomXFlag::FLAG_FULL_MEM, None, Some(dataset.clone())).map(Mutex::new))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(KeyRotationManager {
+            flags,
+            threads,
+            state: RwLock::new(KeyState {
+                key: key.to_vec(),
+                cache,
+                dataset,
+                previous: None,
+            }),
+            vms,
+            pending: Mutex::new(None),
+        })
+    }
+
+    /// Starts building the cache and dataset for `key` on a background thread. They are swapped in once
+    /// [`set_height`](Self::set_height) is called with a height of at least `switch_height`.
+    ///
+    /// A key that is still being prepared is cancelled, and this waits for its build to stop before starting the new
+    /// one, so at most one build runs at a time.
+    pub fn prepare_next_key(&self, key: &[u8], switch_height: u64) -> Result<(), RandomXError> {
+        if key.is_empty() {
+            return Err(RandomXError::ParameterError("key is empty".to_string()));
+        }
+        let mut pending = lock(&self.pending)?;
+        if let Some(previous) = pending.take() {
+            previous.cancel();
+        }
+        let flags = self.flags;
+        let threads = self.threads;
+        let next_key = key.to_vec();
+        let cancel = Arc::new(AtomicBool::new(false));
+        let build_cancel = cancel.clone();
+        let handle = thread::spawn(move || build(flags, &next_key, threads, &build_cancel));
+        *pending = Some(PendingKey {
+            key: key.to_vec(),
+            switch_height,
+            cancel,
+            handle,
+        });
+        Ok(())
+    }
+
+    /// Returns true if a key is being prepared and its cache and dataset are ready to be swapped in.
+    #[allow(unknown_lints, clippy::unnecessary_map_or)] // is_some_and needs Rust 1.70
+    pub fn is_next_key_ready(&self) -> Result<bool, RandomXError> {
+        Ok(lock(&self.pending)?
+            .as_ref()
+            .map_or(false, |pending| pending.handle.is_finished()))
+    }
+
+    /// Advances the manager to `height`. If a key is pending and `height` has reached its switch height, waits for its
+    /// dataset to be ready and attaches it to every VM. Returns true if the key was switched.
+    ///
+    /// If building the pending key failed, the error is returned, the key is discarded and the VMs keep using the
+    /// current key.
+    ///
+    /// Switching locks every VM, so it waits until guards returned by [`vm`](Self::vm) on other threads are dropped.
+    /// The calling thread must not hold such a guard itself, or it deadlocks.
+    pub fn set_height(&self, height: u64) -> Result<bool, RandomXError> {
+        let pending = {
+            let mut pending = lock(&self.pending)?;
+            match pending.as_ref() {
+                Some(next) if height >= next.switch_height => pending.take(),
+                _ => None,
+            }
+        };
+        let pending = match pending {
+            Some(pending) => pending,
+            None => return Ok(false),
+        };
+        let (cache, dataset) = pending
+            .handle
+            .join()
+            .map_err(|_| RandomXError::Other("Dataset build thread panicked".to_string()))??;
+
+        // Hold every VM while swapping, so that no VM hashes with a different key than the others
+        let mut vms = self.vms.iter().map(lock).collect::<Result<Vec<_>, _>>()?;
+        for vm in &mut vms {
+            vm.reinit_dataset(dataset.clone())?;
+        }
+        let mut state = self
+            .state
+            .write()
+            .map_err(|_| RandomXError::Other("Key state lock poisoned".to_string()))?;
+        let previous_key = mem::replace(&mut state.key, pending.key);
+        let previous_cache = mem::replace(&mut state.cache, cache);
+        state.dataset = dataset;
+        state.previous = Some((previous_key, previous_cache));
+        Ok(true)
+    }
+
+    /// Returns the number of VMs owned by the manager.
+    pub fn vm_count(&self) -> usize {
+        self.vms.len()
+    }
+
+    /// Locks and returns the VM at `index`. While the VM is held, the key returned by
+    /// [`current_key`](Self::current_key) is the key it hashes with, and [`set_height`](Self::set_height) cannot
+    /// switch keys. Drop the guard before calling `set_height` on the same thread.
+    pub fn vm(&self, index: usize) -> Result<MutexGuard<'_, RandomXVM>, RandomXError> {
+        let vm = self
+            .vms
+            .get(index)
+            .ok_or_else(|| RandomXError::ParameterError(format!("no VM at index {index}")))?;
+        lock(vm)
+    }
+
+    /// Returns the key the VMs currently hash with.
+    pub fn current_key(&self) -> Result<Vec<u8>, RandomXError> {
+        Ok(self.read_state()?.key.clone())
+    }
+
+    /// Returns the key that was replaced by the last switch, if any.
+    pub fn previous_key(&self) -> Result<Option<Vec<u8>>, RandomXError> {
+        Ok(self.read_state()?.previous.as_ref().map(|(key, _)| key.clone()))
+    }
+
+    /// Returns the dataset of the current key.
+    pub fn current_dataset(&self) -> Result<RandomXDataset, RandomXError> {
+        Ok(self.read_state()?.dataset.clone())
+    }
+
+    /// Returns the cache for `key` if it is the current or the previous key.
+    pub fn cache_for_key(&self, key: &[u8]) -> Result<Option<RandomXCache>, RandomXError> {
+        let state = self.read_state()?;
+        if state.key == key {
+            return Ok(Some(state.cache.clone()));
+        }
+        Ok(state
+            .previous
+            .as_ref()
+            .filter(|(previous, _)| previous == key)
+            .map(|(_, cache)| cache.clone()))
+    }
+
+    /// Creates a light-mode VM for `key`, which must be the current or the previous key. This allows blocks that were
+    /// mined with the previous key to be verified after a switch.
+    pub fn light_vm_for_key(&self, key: &[u8]) -> Result<RandomXVM, RandomXError> {
+        let cache = self.cache_for_key(key)?.ok_or_else(|| {
+            RandomXError::ParameterError("key is neither the current nor the previous key".to_string())
+        })?;
+        RandomXVM::new(self.flags & !RandomXFlag::FLAG_FULL_MEM, Some(cache), None)
+    }
+
+    fn read_state(&self) -> Result<RwLockReadGuard<'_, KeyState>, RandomXError> {
+        self.state
+            .read()
+            .map_err(|_| RandomXError::Other("Key state lock poisoned".to_string()))
+    }
+}
+
+impl Drop for KeyRotationManager {
+    fn drop(&mut self) {
+        let pending = match self.pending.get_mut() {
+            Ok(pending) => pending.take(),
+            Err(poisoned) => poisoned.into_inner().take(),
+        };
+        if let Some(pending) = pending {
+            pending.cancel();
+        }
+    }
+}
+
+fn build(flags: RandomXFlag, key: &[u8], threads: u32, cancel: &AtomicBool) -> BuildResult {
+    let cache = RandomXCache::new(flags, key)?;
+    if cancel.load(Ordering::Relaxed) {
+        return Err(RandomXError::Cancelled);
+    }
+    let dataset = RandomXDataset::new_parallel_with_cancel(flags, cache.clone(), threads, cancel)?;
+    Ok((cache, dataset))
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> Result<MutexGuard<'_, T>, RandomXError> {
+    mutex
+        .lock()
+        .map_err(|_| RandomXError::Other("Lock poisoned".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{KeyRotationManager, RandomXCache, RandomXFlag, RandomXVM};
+
+    #[test]
+    fn key_rotation() {
+        let flags = RandomXFlag::get_recommended_flags();
+        let input = b"Input";
+        let light_hash = |key: &[u8]| {
+            let cache = RandomXCache::new(flags, key).unwrap();
+            let vm = RandomXVM::new(flags, Some(cache), None).unwrap();
+            vm.calculate_hash(input).unwrap()
+        };
+
+        let manager = KeyRotationManager::new(flags, b"Key 1", 2, 4).unwrap();
+        // Preparing another key cancels the build of the first one
+        manager.prepare_next_key(b"Key 3", 10).unwrap();
+        assert_eq!(manager.vm_count(), 2);
+        assert!(manager.vm(2).is_err());
+        assert_eq!(
+            manager.vm(0).unwrap().calculate_hash(input).unwrap(),
+            light_hash(b"Key 1")
+        );
+        assert!(manager.prepare_next_key(b"", 10).is_err());
+
+        manager.prepare_next_key(b"Key 2", 10).unwrap();
+        assert!(!manager.set_height(9).unwrap());
+        assert_eq!(manager.current_key().unwrap(), b"Key 1");
+        assert!(manager.set_height(10).unwrap());
+        assert!(!manager.set_height(11).unwrap());
+        assert!(!manager.is_next_key_ready().unwrap());
+
+        assert_eq!(manager.current_key().unwrap(), b"Key 2");
+        assert_eq!(manager.previous_key().unwrap(), Some(b"Key 1".to_vec()));
+        for i in 0..manager.vm_count() {
+            assert_eq!(
+                manager.vm(i).unwrap().calculate_hash(input).unwrap(),
+                light_hash(b"Key 2")
+            );
+        }
+
+        let previous = manager.light_vm_for_key(b"Key 1").unwrap();
+        assert_eq!(previous.calculate_hash(input).unwrap(), light_hash(b"Key 1"));
+        assert!(manager.light_vm_for_key(b"Key 3").is_err());
+    }
+}
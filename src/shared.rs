@@ -0,0 +1,356 @@
+// Copyright 2026. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Datasets in POSIX shared memory, built by one process and mapped by others on the same host.
+//!
+//! A segment outlives every process that created or mapped it: it stays in `/dev/shm`, holding the whole dataset
+//! (over 2 GiB), until [`RandomXDataset::remove_shared`] is called or the host restarts. Dropping the dataset only
+//! unmaps it.
+
+use std::{
+    convert::TryFrom,
+    ffi::{CStr, CString},
+    io,
+    ptr,
+    slice,
+    sync::{
+        atomic::{AtomicBool, AtomicU32, Ordering},
+        Arc,
+    },
+};
+
+use libc::c_void;
+
+use crate::{
+    bindings::randomx_dataset,
+    checksum::key_fingerprint,
+    DatasetHandle,
+    RandomXCache,
+    RandomXDataset,
+    RandomXDatasetInner,
+    RandomXError,
+};
+
+const MAGIC: [u8; 8] = *b"RXSHMDS\0";
+const VERSION: u32 = 1;
+// The dataset starts on the page after the header, which keeps it aligned for RandomX
+const HEADER_SIZE: usize = 4096;
+const KEY_OFFSET: usize = 24;
+const MAX_KEY_SIZE: usize = HEADER_SIZE - KEY_OFFSET;
+const STATE_INITIALIZING: u32 = 0;
+const STATE_READY: u32 = 1;
+
+/// A dataset mapped from a POSIX shared memory segment.
+///
+/// The segment starts with a header page holding the key the dataset was built for and whether it is ready, followed
+/// by the dataset items.
+#[derive(Debug)]
+pub(crate) struct SharedMapping {
+    base: *mut u8,
+    len: usize,
+    handle: *mut DatasetHandle,
+    key: Vec<u8>,
+}
+
+impl SharedMapping {
+    pub(crate) fn key(&self) -> &[u8] {
+        &self.key
+    }
+
+    fn state(&self) -> &AtomicU32 {
+        // SAFETY: The mapping is page aligned and at least `HEADER_SIZE` bytes long
+        unsafe { &*self.base.add(8).cast::<AtomicU32>() }
+    }
+}
+
+impl Drop for SharedMapping {
+    fn drop(&mut self) {
+        unsafe {
+            drop(Box::from_raw(self.handle));
+            libc::munmap(self.base.cast::<c_void>(), self.len);
+        }
+    }
+}
+
+impl RandomXDataset {
+    /// Creates a new dataset in the shared memory segment for `name` and the key of `cache`, and initializes it using
+    /// `threads` threads. Other processes on the host can then attach the dataset with
+    /// [`RandomXDataset::open_shared`] instead of building their own.
+    ///
+    /// The segment is named after `name` and a fingerprint of the key, so datasets for different keys never share a
+    /// segment. An existing segment with the same name is replaced; processes that already mapped it keep their
+    /// mapping.
+    ///
+    /// # Cleanup
+    ///
+    /// The segment is not removed when the dataset is dropped or the process exits, so other processes can still
+    /// attach it. It keeps occupying the size of the dataset in `/dev/shm` until the host restarts, unless the caller
+    /// removes it with [`RandomXDataset::remove_shared`] once it is no longer needed, e.g. after switching keys.
+    pub fn new_shared(cache: RandomXCache, name: &str, threads: u32) -> Result<RandomXDataset, RandomXError> {
+        if threads == 0 {
+            return Err(RandomXError::ParameterError(
+                "threads must be greater than 0".to_string(),
+            ));
+        }
+        let key = cache.inner.key.clone();
+        if key.len() > MAX_KEY_SIZE {
+            return Err(RandomXError::ParameterError(format!(
+                "keys of shared datasets are limited to {MAX_KEY_SIZE} bytes"
+            )));
+        }
+        let segment = segment_name(name, &key)?;
+        let item_count = RandomXDataset::count()?;
+        let len = HEADER_SIZE + RandomXDataset::memory_size()?;
+
+        unsafe {
+            libc::shm_unlink(segment.as_ptr());
+        }
+        let mapping = SharedMapping::new(map_segment(&segment, true, len)?, len, key);
+        // SAFETY: The mapping is writable and at least `HEADER_SIZE` bytes long
+        let header = unsafe { slice::from_raw_parts_mut(mapping.base, HEADER_SIZE) };
+        header[..8].copy_from_slice(&MAGIC);
+        header[12..16].copy_from_slice(&VERSION.to_le_bytes());
+        header[16..20].copy_from_slice(&item_count.to_le_bytes());
+        header[20..24].copy_from_slice(&u32::try_from(mapping.key.len())?.to_le_bytes());
+        header[KEY_OFFSET..KEY_OFFSET + mapping.key.len()].copy_from_slice(&mapping.key);
+        mapping.state().store(STATE_INITIALIZING, Ordering::Release);
+
+        let dataset = RandomXDataset::from_mapping(Some(cache), item_count, mapping);
+        dataset.init_items_parallel(threads, &AtomicBool::new(false))?;
+        if let Some(mapping) = &dataset.inner.shared {
+            mapping.state().store(STATE_READY, Ordering::Release);
+        }
+        Ok(dataset)
+    }
+
+    /// Maps the dataset that another process created with [`RandomXDataset::new_shared`] for `name` and `key`. The
+    /// mapping is read-only and can be attached to fast-mode VMs like any other dataset.
+    ///
+    /// `RandomXError::SharedDatasetStale` is returned if the segment holds a dataset for another key or other RandomX
+    /// parameters, or if it is still being initialized.
+    pub fn open_shared(name: &str, key: &[u8]) -> Result<RandomXDataset, RandomXError> {
+        let segment = segment_name(name, key)?;
+        let item_count = RandomXDataset::count()?;
+        let len = HEADER_SIZE + RandomXDataset::memory_size()?;
+
+        let mapping = SharedMapping::new(map_segment(&segment, false, len)?, len, key.to_vec());
+        let ready = mapping.state().load(Ordering::Acquire) == STATE_READY;
+        // SAFETY: The mapping is at least `HEADER_SIZE` bytes long
+        let header = unsafe { slice::from_raw_parts(mapping.base, HEADER_SIZE) };
+        check_header(header, key, item_count, ready)?;
+        Ok(RandomXDataset::from_mapping(None, item_count, mapping))
+    }
+
+    /// Removes the shared memory segment for `name` and `key`, freeing its memory once every process has unmapped it.
+    /// Processes that mapped it keep their mapping. Every segment created with [`RandomXDataset::new_shared`] must be
+    /// removed this way, nothing else removes it.
+    pub fn remove_shared(name: &str, key: &[u8]) -> Result<(), RandomXError> {
+        let segment = segment_name(name, key)?;
+        if unsafe { libc::shm_unlink(segment.as_ptr()) } == 0 {
+            Ok(())
+        } else {
+            Err(os_error())
+        }
+    }
+
+    fn from_mapping(cache: Option<RandomXCache>, item_count: u32, mapping: SharedMapping) -> RandomXDataset {
+        RandomXDataset {
+            inner: Arc::new(RandomXDatasetInner {
+                dataset_ptr: mapping.handle.cast::<randomx_dataset>(),
+                dataset_count: item_count,
+                large_pages: false,
+                cache,
+                shared: Some(mapping),
+            }),
+        }
+    }
+}
+
+impl SharedMapping {
+    fn new(base: *mut u8, len: usize, key: Vec<u8>) -> Self {
+        let handle = Box::into_raw(Box::new(DatasetHandle {
+            // SAFETY: The mapping is `HEADER_SIZE` bytes longer than the dataset
+            memory: unsafe { base.add(HEADER_SIZE) },
+            dealloc: ptr::null(),
+        }));
+        Self { base, len, handle, key }
+    }
+}
+
+/// Returns the segment name for `name` and `key`, e.g. `/miner-1a2b3c4d5e6f7a8b`.
+fn segment_name(name: &str, key: &[u8]) -> Result<CString, RandomXError> {
+    if name.is_empty() || name.len() > 200 || name.contains('/') {
+        return Err(RandomXError::ParameterError(
+            "name must be 1 to 200 characters long and must not contain '/'".to_string(),
+        ));
+    }
+    if key.is_empty() {
+        return Err(RandomXError::ParameterError("key is empty".to_string()));
+    }
+    CString::new(format!("/{name}-{:016x}", key_fingerprint(key)))
+        .map_err(|_| RandomXError::ParameterError("name must not contain NUL".to_string()))
+}
+
+/// Checks that a segment header describes a ready dataset of `item_count` items built for `key`.
+fn check_header(header: &[u8], key: &[u8], item_count: u32, ready: bool) -> Result<(), RandomXError> {
+    let u32_at = |offset: usize| {
+        let mut bytes = [0u8; 4];
+        bytes.copy_from_slice(&header[offset..offset + 4]);
+        u32::from_le_bytes(bytes)
+    };
+    let stale = |reason: &str| Err(RandomXError::SharedDatasetStale(reason.to_string()));
+    if header[..8] != MAGIC || u32_at(12) != VERSION {
+        return stale("segment was not created by this version of randomx-rs");
+    }
+    if u32_at(16) != item_count {
+        return stale("segment was created with other RandomX parameters");
+    }
+    let key_len = usize::try_from(u32_at(20))?;
+    if key_len > MAX_KEY_SIZE || header[KEY_OFFSET..KEY_OFFSET + key_len] != *key {
+        return stale("segment holds a dataset for another key");
+    }
+    if !ready {
+        return stale("dataset is still being initialized");
+    }
+    Ok(())
+}
+
+/// Maps the segment named `segment`, `len` bytes long. A writable segment is created, and removed again if it cannot
+/// be mapped. A read-only segment must exist and be exactly `len` bytes long.
+fn map_segment(segment: &CStr, writable: bool, len: usize) -> Result<*mut u8, RandomXError> {
+    let (flags, protection) = if writable {
+        (
+            libc::O_CREAT | libc::O_EXCL | libc::O_RDWR,
+            libc::PROT_READ | libc::PROT_WRITE,
+        )
+    } else {
+        (libc::O_RDONLY, libc::PROT_READ)
+    };
+    let fd = unsafe { libc::shm_open(segment.as_ptr(), flags, 0o644) };
+    if fd < 0 {
+        return Err(os_error());
+    }
+    let result = size_segment(fd, writable, len).and_then(|()| {
+        let base = unsafe { libc::mmap(ptr::null_mut(), len, protection, libc::MAP_SHARED, fd, 0) };
+        if base == libc::MAP_FAILED {
+            Err(os_error())
+        } else {
+            Ok(base.cast::<u8>())
+        }
+    });
+    unsafe {
+        libc::close(fd);
+        if writable && result.is_err() {
+            libc::shm_unlink(segment.as_ptr());
+        }
+    }
+    result
+}
+
+/// Sets the size of a new segment, or checks the size of an existing one.
+fn size_segment(fd: libc::c_int, writable: bool, len: usize) -> Result<(), RandomXError> {
+    if writable {
+        return if unsafe { libc::ftruncate(fd, libc::off_t::try_from(len)?) } == 0 {
+            Ok(())
+        } else {
+            Err(os_error())
+        };
+    }
+    let mut stat = unsafe { std::mem::zeroed::<libc::stat>() };
+    if unsafe { libc::fstat(fd, &mut stat) } != 0 {
+        return Err(os_error());
+    }
+    if usize::try_from(stat.st_size)? == len {
+        Ok(())
+    } else {
+        Err(RandomXError::SharedDatasetStale(format!(
+            "segment is {} bytes, expected {len}",
+            stat.st_size
+        )))
+    }
+}
+
+fn os_error() -> RandomXError {
+    RandomXError::IoError(io::Error::last_os_error().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_header, segment_name, HEADER_SIZE, KEY_OFFSET, MAGIC, VERSION};
+    use crate::{RandomXCache, RandomXDataset, RandomXError, RandomXFlag, RandomXVM};
+
+    #[test]
+    fn shared_dataset_header() {
+        let mut header = [0u8; HEADER_SIZE];
+        header[..8].copy_from_slice(&MAGIC);
+        header[12..16].copy_from_slice(&VERSION.to_le_bytes());
+        header[16..20].copy_from_slice(&10u32.to_le_bytes());
+        header[20..24].copy_from_slice(&3u32.to_le_bytes());
+        header[KEY_OFFSET..KEY_OFFSET + 3].copy_from_slice(b"Key");
+
+        assert!(check_header(&header, b"Key", 10, true).is_ok());
+        for (key, item_count, ready) in [
+            (&b"Kez"[..], 10, true),
+            (b"Key 2", 10, true),
+            (b"Key", 11, true),
+            (b"Key", 10, false),
+        ] {
+            assert!(matches!(
+                check_header(&header, key, item_count, ready),
+                Err(RandomXError::SharedDatasetStale(_))
+            ));
+        }
+        header[12] = 2;
+        assert!(check_header(&header, b"Key", 10, true).is_err());
+
+        assert_ne!(
+            segment_name("test", b"Key 1").unwrap(),
+            segment_name("test", b"Key 2").unwrap()
+        );
+        assert!(segment_name("", b"Key").is_err());
+        assert!(segment_name("a/b", b"Key").is_err());
+        assert!(segment_name("test", b"").is_err());
+    }
+
+    #[test]
+    fn shared_dataset_across_mappings() {
+        let flags = RandomXFlag::get_recommended_flags();
+        let name = format!("randomx-rs-test-{}", std::process::id());
+        let cache = RandomXCache::new(flags, b"Key").unwrap();
+        let light_hash = RandomXVM::new(flags, Some(cache.clone()), None)
+            .unwrap()
+            .calculate_hash(b"Input")
+            .unwrap();
+
+        let shared = RandomXDataset::new_shared(cache, &name, 4).unwrap();
+        assert!(RandomXDataset::open_shared(&name, b"Other key").is_err());
+        let opened = RandomXDataset::open_shared(&name, b"Key").unwrap();
+        RandomXDataset::remove_shared(&name, b"Key").unwrap();
+        assert!(RandomXDataset::open_shared(&name, b"Key").is_err());
+
+        assert!(opened.memory().unwrap() == shared.memory().unwrap());
+        for dataset in [shared, opened] {
+            let vm = RandomXVM::new(flags | RandomXFlag::FLAG_FULL_MEM, None, Some(dataset)).unwrap();
+            assert_eq!(vm.calculate_hash(b"Input").unwrap(), light_hash);
+        }
+    }
+}
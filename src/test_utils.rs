@@ -58,7 +58,6 @@ pub fn fuzz_randomx_alloc_cache(data: Vec<u8>) -> bool {
 /// - `pub fn randomx_init_cache`
 /// - `pub fn randomx_release_cache`
 #[allow(clippy::needless_pass_by_value)] // This is required by the `QuickCheck` fuzzing framework
-
 pub fn fuzz_randomx_create_vm_with_cache_only(data: Vec<u8>) -> bool {
     let flags = RandomXFlag::get_recommended_flags();
     if let Ok(cache) = RandomXCache::new(flags, &data) {
@@ -91,7 +90,7 @@ pub fn fuzz_randomx_create_vm_with_cache_and_dataset(data: Vec<u8>) -> bool {
         let start = if data.is_empty() { 0u32 } else { u32::from(data[0] % 3) };
         if let Ok(dataset) = RandomXDataset::new(flags, cache.clone(), start) {
             for _ in 0..100 {
-                let _unused = dataset.get_data();
+                let _unused = dataset.memory();
             }
             if let Ok(mut vm) = RandomXVM::new(flags, Some(cache.clone()), Some(dataset.clone())) {
                 let _unused = vm.reinit_cache(cache);
@@ -134,6 +133,7 @@ fn calculate_hashes(hash_data: &[u8], vm: &mut RandomXVM, iterations: u8) {
 /// - `pub fn randomx_calculate_hash_last`
 /// - `pub fn randomx_calculate_hash_first`
 /// - `pub fn randomx_calculate_hash_next`
+///
 /// Secondary:
 /// - `pub fn randomx_create_vm`
 /// - `pub fn randomx_destroy_vm`
@@ -158,6 +158,7 @@ pub fn fuzz_randomx_vm_calculate_hash_with_cache_only(data: Vec<u8>) -> bool {
 /// - `pub fn randomx_calculate_hash_last`
 /// - `pub fn randomx_calculate_hash_first`
 /// - `pub fn randomx_calculate_hash_next`
+///
 /// Secondary:
 /// - `pub fn randomx_create_vm`
 /// - `pub fn randomx_destroy_vm`